@@ -0,0 +1,80 @@
+//! APIs to check whether a new version of a schema is compatible with its previous versions,
+//! following the same rules as [schema resolution](crate::resolve).
+use crate::resolve::{incompatibilities, Incompatibility};
+use crate::schema::Schema;
+
+/// The compatibility of a new version of a schema with its previous versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compatibility {
+    /// Data written with the previous versions can be read with the new version
+    Backward,
+    /// Data written with the new version can be read with the previous versions
+    Forward,
+    /// Both [`Compatibility::Backward`] and [`Compatibility::Forward`]
+    Full,
+}
+
+/// An incompatibility of a new version of a schema with one of its previous versions
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersionIncompatibility {
+    /// The index of the previous version
+    pub version: usize,
+    /// Whether the new version cannot read the previous version's data ([`Compatibility::Backward`])
+    /// or the previous version cannot read the new version's data ([`Compatibility::Forward`])
+    pub compatibility: Compatibility,
+    /// The incompatibility
+    pub incompatibility: Incompatibility,
+}
+
+impl std::fmt::Display for VersionIncompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = match self.compatibility {
+            Compatibility::Forward => "forward",
+            _ => "backward",
+        };
+        write!(
+            f,
+            "version {} ({}): {}",
+            self.version, direction, self.incompatibility
+        )
+    }
+}
+
+/// Returns every incompatibility of `schema` with each of the `previous` versions
+/// (e.g. the last N versions of a schema registry's subject) under `compatibility`.
+/// `schema` is compatible with them iff none is returned.
+pub fn check(
+    schema: &Schema,
+    previous: &[Schema],
+    compatibility: Compatibility,
+) -> Vec<VersionIncompatibility> {
+    let backward = matches!(compatibility, Compatibility::Backward | Compatibility::Full);
+    let forward = matches!(compatibility, Compatibility::Forward | Compatibility::Full);
+
+    let mut result = vec![];
+    for (version, previous) in previous.iter().enumerate() {
+        if backward {
+            result.extend(
+                incompatibilities(previous, schema)
+                    .into_iter()
+                    .map(|incompatibility| VersionIncompatibility {
+                        version,
+                        compatibility: Compatibility::Backward,
+                        incompatibility,
+                    }),
+            );
+        }
+        if forward {
+            result.extend(
+                incompatibilities(schema, previous)
+                    .into_iter()
+                    .map(|incompatibility| VersionIncompatibility {
+                        version,
+                        compatibility: Compatibility::Forward,
+                        incompatibility,
+                    }),
+            );
+        }
+    }
+    result
+}
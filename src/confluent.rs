@@ -0,0 +1,64 @@
+//! APIs to read and write messages with the Confluent schema registry's
+//! [wire format](https://docs.confluent.io/platform/current/schema-registry/fundamentals/serdes-develop/index.html#wire-format):
+//! the magic byte `0`, followed by the 4-byte big-endian id of the schema in the registry,
+//! followed by the Avro data of a single value.
+use std::io::{Read, Write};
+
+use crate::error::Error;
+use crate::read::Decoder;
+use crate::schema::Schema;
+use crate::store::SchemaStore;
+use crate::value::Value;
+use crate::write::Encoder;
+
+/// The magic byte at the start of every message
+pub const MAGIC: u8 = 0;
+
+/// Writes the header of a message of the schema with `id`.
+pub fn write_header<W: Write>(writer: &mut W, id: u32) -> Result<(), Error> {
+    writer.write_all(&[MAGIC])?;
+    writer.write_all(&id.to_be_bytes())?;
+    Ok(())
+}
+
+/// Reads the header of a message, returning the id of its schema.
+/// # Error
+/// This function errors iff the message does not start with [`MAGIC`] or is shorter than the header.
+pub fn read_header<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut magic = [0u8; 1];
+    reader.read_exact(&mut magic)?;
+    if magic[0] != MAGIC {
+        return Err(Error::InvalidConfluentMagic(magic[0]));
+    }
+    let mut id = [0u8; 4];
+    reader.read_exact(&mut id)?;
+    Ok(u32::from_be_bytes(id))
+}
+
+/// Writes `value` of `schema`, registered with `id`, as a message.
+/// # Error
+/// This function errors iff `value` is not described by `schema`.
+pub fn encode<W: Write>(
+    value: &Value,
+    schema: &Schema,
+    id: u32,
+    writer: &mut W,
+) -> Result<(), Error> {
+    write_header(writer, id)?;
+    Encoder::new(schema).encode(value, writer)
+}
+
+/// Reads a message, whose schema is resolved by its id from `store`.
+/// Returns the value and its schema.
+/// # Error
+/// This function errors iff the message has an invalid header, its id is not in `store`
+/// or its data is not described by the schema.
+pub fn decode<'a, R: Read, S: SchemaStore<u32>>(
+    reader: &mut R,
+    store: &'a S,
+) -> Result<(Value, &'a Schema), Error> {
+    let id = read_header(reader)?;
+    let schema = store.get(&id).ok_or(Error::UnknownSchemaId(id))?;
+    let value = Decoder::new(schema).decode(reader)?;
+    Ok((value, schema))
+}
@@ -1,22 +1,162 @@
 //! Contains [`Error`]
 
 /// Error from this crate
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 pub enum Error {
-    /// Generic error when the file is out of spec
-    OutOfSpec,
-    /// When reading or writing with compression but the feature flag "compression" is not active.
+    /// Generic error when the file or its data is out of spec, with a description of why
+    OutOfSpec(String),
+    /// When reading or writing with compression but the feature flag of the compression
+    /// ("compression", "zstd", "bzip2" or "xz2") is not active.
     RequiresCompression,
+    /// When reading a file whose codec (`avro.codec`) is not known to this crate.
+    UnknownCodec(String),
+    /// When the file does not start with Avro's magic bytes, `Obj1`.
+    InvalidMagic([u8; 4]),
+    /// When the file's schema (`avro.schema`) cannot be (de)serialized.
+    InvalidSchema(serde_json::Error),
+    /// When a schema violates the spec, see [`Schema::validate`](crate::schema::Schema::validate).
+    SchemaViolations(Vec<crate::schema::Violation>),
+    /// When the sync marker after a block does not match the file's marker.
+    SyncMarkerMismatch {
+        /// The index of the block, starting from the first block read by the iterator
+        block: usize,
+        /// The position, in bytes, of the block in the file, see
+        /// [`BlockStreamingIterator::with_position`](crate::read::BlockStreamingIterator::with_position)
+        offset: u64,
+        /// The file's marker
+        expected: [u8; 16],
+        /// The marker found after the block
+        actual: [u8; 16],
+    },
+    /// When a block of a file cannot be decompressed.
+    CorruptBlock {
+        /// The index of the block, starting from the first block read by the iterator
+        block: usize,
+        /// The position, in bytes, of the block in the file, see
+        /// [`BlockStreamingIterator::with_position`](crate::read::BlockStreamingIterator::with_position)
+        offset: u64,
+        /// The error decompressing the block
+        error: Box<Error>,
+    },
+    /// When the checksum of a decompressed block does not match the one stored with it.
+    ChecksumMismatch {
+        /// The stored checksum
+        expected: u32,
+        /// The checksum of the decompressed block
+        actual: u32,
+    },
+    /// When a [`Value`](crate::value::Value) is not described by the schema it is written with
+    InvalidValue(String),
+    /// When a single-object encoded message does not start with its marker, `C3 01`.
+    InvalidSingleObjectMarker([u8; 2]),
+    /// When the fingerprint of a single-object encoded message is not in the schema store.
+    UnknownFingerprint(u64),
+    /// When a message in Confluent's wire format does not start with its magic byte, `0`.
+    InvalidConfluentMagic(u8),
+    /// When the schema id of a message in Confluent's wire format is not in the schema store.
+    UnknownSchemaId(u32),
+    /// When data written with a schema cannot be read with another schema.
+    IncompatibleSchemas(Vec<crate::resolve::Incompatibility>),
+    /// An error from the underlying reader or writer
+    Io(std::io::Error),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Error::OutOfSpec(reason) => write!(f, "out of spec: {}", reason),
+            Error::RequiresCompression => write!(
+                f,
+                "the file is compressed but the feature flag of its compression is not active"
+            ),
+            Error::UnknownCodec(codec) => write!(f, "the codec \"{}\" is not supported", codec),
+            Error::InvalidMagic(magic) => write!(
+                f,
+                "the file does not start with Avro's magic bytes (found {:?})",
+                magic
+            ),
+            Error::InvalidSchema(error) => write!(f, "invalid schema: {}", error),
+            Error::SchemaViolations(violations) => {
+                write!(f, "invalid schema: ")?;
+                for (i, violation) in violations.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", violation)?;
+                }
+                Ok(())
+            }
+            Error::SyncMarkerMismatch {
+                block,
+                offset,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "the sync marker of block {} (at offset {}) is {:?} but the file's marker is {:?}",
+                block, offset, actual, expected
+            ),
+            Error::CorruptBlock {
+                block,
+                offset,
+                error,
+            } => write!(
+                f,
+                "block {} (at offset {}) cannot be decompressed: {}",
+                block, offset, error
+            ),
+            Error::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "the checksum of the decompressed block is {:#010x} but {:#010x} was expected",
+                actual, expected
+            ),
+            Error::InvalidValue(reason) => write!(f, "invalid value: {}", reason),
+            Error::InvalidSingleObjectMarker(marker) => write!(
+                f,
+                "the message does not start with the single-object marker (found {:?})",
+                marker
+            ),
+            Error::UnknownFingerprint(fingerprint) => write!(
+                f,
+                "the schema with fingerprint {:#018x} is not in the store",
+                fingerprint
+            ),
+            Error::InvalidConfluentMagic(magic) => write!(
+                f,
+                "the message does not start with Confluent's magic byte (found {})",
+                magic
+            ),
+            Error::UnknownSchemaId(id) => {
+                write!(f, "the schema with id {} is not in the store", id)
+            }
+            Error::IncompatibleSchemas(incompatibilities) => {
+                write!(f, "incompatible schemas: ")?;
+                for (i, incompatibility) in incompatibilities.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", incompatibility)?;
+                }
+                Ok(())
+            }
+            Error::Io(error) => write!(f, "io error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidSchema(error) => Some(error),
+            Error::CorruptBlock { error, .. } => Some(error.as_ref()),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
     }
 }
 
 impl From<std::io::Error> for Error {
-    fn from(_: std::io::Error) -> Self {
-        Error::OutOfSpec
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
     }
 }
@@ -1,15 +1,50 @@
 //! Contains structs found in Avro files
-use crate::schema::Record;
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::schema::{Record, Schema};
 
 /// Avro file's Metadata
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct FileMetadata {
-    /// The Record represented in the file's Schema
-    pub record: Record,
+    /// The file's Schema, describing each of its rows
+    pub schema: Schema,
     /// The files' compression
     pub compression: Option<Compression>,
     /// The files' marker, present in every block
     pub marker: [u8; 16],
+    /// The files' key-value metadata.
+    /// When read, it contains all entries of the header, including the reserved `avro.schema` and `avro.codec`.
+    /// When written, `avro.schema` and `avro.codec` are ignored, since they are derived from the other fields,
+    /// and no other key may start with `avro.`.
+    pub metadata: BTreeMap<String, Vec<u8>>,
+}
+
+impl FileMetadata {
+    /// Returns a new [`FileMetadata`] with a randomly generated marker.
+    /// # Error
+    /// This function errors iff the operating system fails to provide random bytes.
+    pub fn try_new<S: Into<Schema>>(
+        schema: S,
+        compression: Option<Compression>,
+    ) -> Result<Self, Error> {
+        let mut marker = [0u8; 16];
+        getrandom::getrandom(&mut marker).map_err(std::io::Error::from)?;
+        Ok(Self {
+            schema: schema.into(),
+            compression,
+            marker,
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the [`Record`] of the file's schema, if it is a record (the most common case).
+    pub fn record(&self) -> Option<&Record> {
+        match &self.schema {
+            Schema::Record(record) => Some(record),
+            _ => None,
+        }
+    }
 }
 
 /// A compressed Avro block.
@@ -57,4 +92,10 @@ pub enum Compression {
     Deflate,
     /// Snappy
     Snappy,
+    /// Zstandard
+    Zstandard,
+    /// Bzip2
+    Bzip2,
+    /// XZ
+    Xz,
 }
@@ -2,14 +2,20 @@
 #![forbid(unsafe_code)]
 #![forbid(missing_docs)]
 
+pub mod compatibility;
+pub mod confluent;
 pub mod error;
 pub mod file;
 pub mod schema;
+pub mod single_object;
+pub mod store;
+pub mod value;
 
 pub mod read;
 #[cfg(feature = "async")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub mod read_async;
+pub mod resolve;
 
 pub mod write;
 #[cfg(feature = "async")]
@@ -1,5 +1,5 @@
 //! APIs to read from Avro format to arrow.
-use std::io::Read;
+use std::io::{Cursor, Read};
 
 use fallible_streaming_iterator::FallibleStreamingIterator;
 
@@ -7,53 +7,161 @@ use crate::{error::Error, file::CompressedBlock};
 
 use super::decode;
 
-fn read_size<R: Read>(reader: &mut R) -> Result<(usize, usize), Error> {
+/// Reads the number of rows and bytes of the `index`-th block, at `offset`, or `None` at the
+/// end of the file
+pub(crate) fn read_size<R: Read>(
+    reader: &mut R,
+    index: usize,
+    offset: u64,
+) -> Result<Option<(usize, usize)>, Error> {
     let rows = match decode::internal_zigzag_i64(reader) {
         Ok(a) => a,
         Err(error) => match error {
-            decode::DecodeError::EndOfFile => return Ok((0, 0)),
-            decode::DecodeError::OutOfSpec => return Err(Error::OutOfSpec),
+            decode::DecodeError::EndOfFile => return Ok(None),
+            error => return Err(error.into()),
         },
     };
-    let bytes = decode::zigzag_i64(reader)?;
+    let bytes = match decode::internal_zigzag_i64(reader) {
+        Ok(a) => a,
+        Err(error) => match error {
+            decode::DecodeError::EndOfFile => return Err(truncated(index, offset)),
+            error => return Err(error.into()),
+        },
+    };
+    block_size(rows, bytes).map(Some)
+}
+
+/// Validates the number of rows and bytes of a block
+pub(crate) fn block_size(rows: i64, bytes: i64) -> Result<(usize, usize), Error> {
+    if rows < 0 || bytes < 0 {
+        return Err(Error::OutOfSpec(format!(
+            "a block must have a non-negative number of rows and bytes (got {} and {})",
+            rows, bytes
+        )));
+    }
     Ok((rows as usize, bytes as usize))
 }
 
-/// Reads a [`CompressedBlock`] from the `reader`.
+/// The error of the `index`-th block, at `offset`, when the file ends before its sync marker
+pub(crate) fn truncated(index: usize, offset: u64) -> Error {
+    Error::OutOfSpec(format!(
+        "block {} (at offset {}) is truncated",
+        index, offset
+    ))
+}
+
+/// The error of a block without rows with `bytes` bytes of data. Blocks without rows are only
+/// valid without data, and are skipped as they carry no rows.
+pub(crate) fn rows_without_data(bytes: usize) -> Error {
+    Error::OutOfSpec(format!("a block without rows has {} bytes of data", bytes))
+}
+
+/// Returns the number of bytes of a block with `rows` rows and `bytes` bytes, including its
+/// header and sync marker
+pub(crate) fn block_len(rows: usize, bytes: usize) -> u64 {
+    decode::zigzag_len(rows as i64) + decode::zigzag_len(bytes as i64) + bytes as u64 + 16
+}
+
+/// Reads `reader` up to and including the next occurrence of `marker`, returning the number
+/// of bytes read, or `None` if `reader` ends before it.
+pub(crate) fn skip_to_marker<R: Read>(
+    reader: &mut R,
+    marker: [u8; 16],
+) -> Result<Option<u64>, Error> {
+    let mut window = [0u8; 16];
+    let mut read = 0u64;
+    let mut byte = [0u8; 1];
+    loop {
+        match reader.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        }
+        window.copy_within(1.., 0);
+        window[15] = byte[0];
+        read += 1;
+        if read >= 16 && window == marker {
+            return Ok(Some(read));
+        }
+    }
+}
+
+/// Reads the data and sync marker of a [`CompressedBlock`] of `bytes` bytes from the `reader`.
+/// `index` and `offset` are the position of the block, used to report errors.
+/// When it errors, `block.data` contains every byte read after the header of the block.
 /// # Error
 /// This function errors iff either the block cannot be read or the sync marker does not match
 fn read_block<R: Read>(
     reader: &mut R,
     block: &mut CompressedBlock,
+    bytes: usize,
     marker: [u8; 16],
+    index: usize,
+    offset: u64,
 ) -> Result<(), Error> {
-    let (rows, bytes) = read_size(reader)?;
-    block.number_of_rows = rows;
-    if rows == 0 {
-        return Ok(());
-    };
-
     block.data.clear();
-    block
-        .data
-        .try_reserve(bytes)
-        .map_err(|_| Error::OutOfSpec)?;
-    reader.take(bytes as u64).read_to_end(&mut block.data)?;
-
-    let mut block_marker = [0u8; 16];
-    reader.read_exact(&mut block_marker)?;
+    // the data is read together with the marker that follows it
+    block.data.try_reserve(bytes + 16).map_err(|_| {
+        Error::OutOfSpec(format!(
+            "cannot allocate {} bytes for block {} (at offset {})",
+            bytes, index, offset
+        ))
+    })?;
+    reader
+        .take(bytes as u64 + 16)
+        .read_to_end(&mut block.data)?;
+    if block.data.len() < bytes + 16 {
+        return Err(truncated(index, offset));
+    }
 
-    if block_marker != marker {
-        return Err(Error::OutOfSpec);
+    if block.data[bytes..] != marker {
+        let mut actual = [0u8; 16];
+        actual.copy_from_slice(&block.data[bytes..]);
+        return Err(Error::SyncMarkerMismatch {
+            block: index,
+            offset,
+            expected: marker,
+            actual,
+        });
     }
+    block.data.truncate(bytes);
     Ok(())
 }
 
+/// A [`Read`] that first reads the bytes pending from a recovery and counts the bytes read
+struct Tracked<'a, R: Read> {
+    pending: &'a mut Cursor<Vec<u8>>,
+    reader: &'a mut R,
+    read: &'a mut u64,
+}
+
+impl<'a, R: Read> Read for Tracked<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = if self.pending.position() < self.pending.get_ref().len() as u64 {
+            self.pending.read(buf)?
+        } else {
+            self.reader.read(buf)?
+        };
+        *self.read += read as u64;
+        Ok(read)
+    }
+}
+
 /// [`FallibleStreamingIterator`] of [`CompressedBlock`].
 pub struct CompressedBlockStreamingIterator<R: Read> {
     buf: CompressedBlock,
     reader: R,
     marker: [u8; 16],
+    // bytes to read before `reader`, after skipping a corrupt block
+    pending: Cursor<Vec<u8>>,
+    // the position of the last block
+    start: u64,
+    // the number of rows of the last block, if its header could be read
+    rows: Option<usize>,
+    // the index and position of the next block
+    index: usize,
+    offset: u64,
 }
 
 impl<R: Read> CompressedBlockStreamingIterator<R> {
@@ -63,9 +171,23 @@ impl<R: Read> CompressedBlockStreamingIterator<R> {
             reader,
             marker,
             buf: CompressedBlock::new(0, scratch),
+            pending: Cursor::new(vec![]),
+            start: 0,
+            rows: None,
+            index: 0,
+            offset: 0,
         }
     }
 
+    /// Sets the position of `reader` in the file, as
+    /// [`BlockStreamingIterator::with_position`](super::BlockStreamingIterator::with_position)
+    /// does.
+    pub fn with_position(mut self, position: u64) -> Self {
+        self.start = position;
+        self.offset = position;
+        self
+    }
+
     /// The buffer of [`CompressedBlockStreamingIterator`].
     pub fn buffer(&mut self) -> &mut CompressedBlock {
         &mut self.buf
@@ -75,6 +197,89 @@ impl<R: Read> CompressedBlockStreamingIterator<R> {
     pub fn into_inner(self) -> (R, Vec<u8>) {
         (self.reader, self.buf.data)
     }
+
+    /// The position of the next block, see [`Self::with_position`]
+    pub(crate) fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The position of the last block, see [`Self::with_position`]
+    pub(crate) fn block_offset(&self) -> u64 {
+        self.start
+    }
+
+    /// The index of the last block that was read
+    pub(crate) fn block_index(&self) -> usize {
+        self.index.saturating_sub(1)
+    }
+
+    /// The number of rows of the last block, if its header could be read
+    pub(crate) fn block_rows(&self) -> Option<usize> {
+        self.rows
+    }
+
+    /// Reads the next block, including blocks without rows, returning whether there was one,
+    /// i.e. `false` at the end of the file.
+    pub(crate) fn read_next(&mut self) -> Result<bool, Error> {
+        self.start = self.offset;
+        self.rows = None;
+        self.buf.number_of_rows = 0;
+        let mut reader = Tracked {
+            pending: &mut self.pending,
+            reader: &mut self.reader,
+            read: &mut self.offset,
+        };
+        let (rows, bytes) = match read_size(&mut reader, self.index, self.start)? {
+            Some(size) => size,
+            None => return Ok(false),
+        };
+        self.rows = Some(rows);
+        // the block is counted even if it cannot be read, so that the next one has its index
+        self.index += 1;
+        read_block(
+            &mut reader,
+            &mut self.buf,
+            bytes,
+            self.marker,
+            self.index - 1,
+            self.start,
+        )?;
+        self.buf.number_of_rows = rows;
+        Ok(true)
+    }
+
+    /// Skips the last block, which could not be read, up to the next sync marker (or the end
+    /// of the file).
+    pub(crate) fn skip_to_marker(&mut self) -> Result<(), Error> {
+        // the bytes read after the header of the block may contain the next marker: they are
+        // read again, followed by the remaining pending bytes
+        let mut pending = std::mem::take(&mut self.buf.data);
+        self.offset -= pending.len() as u64;
+        let position = self.pending.position() as usize;
+        pending.extend_from_slice(&self.pending.get_ref()[position..]);
+        self.pending = Cursor::new(pending);
+
+        let mut reader = Tracked {
+            pending: &mut self.pending,
+            reader: &mut self.reader,
+            read: &mut self.offset,
+        };
+        skip_to_marker(&mut reader, self.marker)?;
+        self.buf.number_of_rows = 0;
+        Ok(())
+    }
+
+    pub(crate) fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Sets the index and position of the next block, after the reader was moved to it
+    pub(crate) fn set_position(&mut self, index: usize, offset: u64) {
+        self.buf.number_of_rows = 0;
+        self.pending = Cursor::new(vec![]);
+        self.index = index;
+        self.offset = offset;
+    }
 }
 
 impl<R: Read> FallibleStreamingIterator for CompressedBlockStreamingIterator<R> {
@@ -82,7 +287,18 @@ impl<R: Read> FallibleStreamingIterator for CompressedBlockStreamingIterator<R>
     type Item = CompressedBlock;
 
     fn advance(&mut self) -> Result<(), Error> {
-        read_block(&mut self.reader, &mut self.buf, self.marker)?;
+        while self.read_next()? {
+            if self.buf.number_of_rows > 0 {
+                break;
+            }
+            if !self.buf.data.is_empty() {
+                return Err(Error::CorruptBlock {
+                    block: self.block_index(),
+                    offset: self.block_offset(),
+                    error: Box::new(rows_without_data(self.buf.data.len())),
+                });
+            }
+        }
         Ok(())
     }
 
@@ -13,8 +13,13 @@ pub enum DecodeError {
 }
 
 impl From<DecodeError> for Error {
-    fn from(_: DecodeError) -> Self {
-        Error::OutOfSpec
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::OutOfSpec => {
+                Error::OutOfSpec("variable-length integer with more than 10 bytes".to_string())
+            }
+            DecodeError::EndOfFile => Error::Io(std::io::ErrorKind::UnexpectedEof.into()),
+        }
     }
 }
 
@@ -36,6 +41,17 @@ pub fn zigzag_i64<R: Read>(reader: &mut R) -> Result<i64, Error> {
     })
 }
 
+/// Returns the number of bytes of the zigzag encoding of `n`
+pub(crate) fn zigzag_len(n: i64) -> u64 {
+    let mut z = ((n << 1) ^ (n >> 63)) as u64;
+    let mut len = 1;
+    while z > 0x7F {
+        z >>= 7;
+        len += 1;
+    }
+    len
+}
+
 #[inline]
 fn decode_variable<R: Read>(reader: &mut R) -> Result<u64, DecodeError> {
     avro_decode!(reader)
@@ -44,7 +60,8 @@ fn decode_variable<R: Read>(reader: &mut R) -> Result<u64, DecodeError> {
 fn _read_binary<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
     let len: usize = zigzag_i64(reader)? as usize;
     let mut buf = vec![];
-    buf.try_reserve(len).map_err(|_| Error::OutOfSpec)?;
+    buf.try_reserve(len)
+        .map_err(|_| Error::OutOfSpec(format!("cannot allocate {} bytes", len)))?;
     reader.take(len as u64).read_to_end(&mut buf)?;
     Ok(buf)
 }
@@ -61,20 +78,26 @@ pub(crate) fn read_file_marker<R: Read>(reader: &mut R) -> Result<[u8; 16], Erro
 
 /// Deserializes the Avro header into an Avro [`Schema`] and optional [`Compression`].
 pub(crate) fn deserialize_header(
-    header: HashMap<String, Vec<u8>>,
+    header: &HashMap<String, Vec<u8>>,
 ) -> Result<(Schema, Option<Compression>), Error> {
     let schema = header
         .get("avro.schema")
-        .ok_or(Error::OutOfSpec)
-        .and_then(|bytes| serde_json::from_slice(bytes.as_ref()).map_err(|_| Error::OutOfSpec))?;
+        .ok_or_else(|| Error::OutOfSpec("the header has no \"avro.schema\"".to_string()))
+        .and_then(|bytes| serde_json::from_slice(bytes.as_ref()).map_err(Error::InvalidSchema))?;
 
-    let compression = header.get("avro.codec").and_then(|bytes| {
-        let bytes: &[u8] = bytes.as_ref();
-        match bytes {
-            b"snappy" => Some(Compression::Snappy),
-            b"deflate" => Some(Compression::Deflate),
-            _ => None,
+    let compression = match header.get("avro.codec").map(|bytes| bytes.as_slice()) {
+        // the spec's "null" codec is equivalent to no codec
+        None | Some(b"null") => None,
+        Some(b"snappy") => Some(Compression::Snappy),
+        Some(b"deflate") => Some(Compression::Deflate),
+        Some(b"zstandard") => Some(Compression::Zstandard),
+        Some(b"bzip2") => Some(Compression::Bzip2),
+        Some(b"xz") => Some(Compression::Xz),
+        Some(other) => {
+            return Err(Error::UnknownCodec(
+                String::from_utf8_lossy(other).into_owned(),
+            ))
         }
-    });
+    };
     Ok((schema, compression))
 }
@@ -8,7 +8,7 @@ use crate::error::Error;
 use crate::file::Compression;
 use crate::file::{Block, CompressedBlock};
 
-use super::block::CompressedBlockStreamingIterator;
+use super::block::{rows_without_data, CompressedBlockStreamingIterator};
 
 #[cfg(feature = "compression")]
 const CRC_TABLE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
@@ -38,37 +38,89 @@ pub fn decompress_block(
         }
         #[cfg(feature = "compression")]
         Some(Compression::Snappy) => {
+            if block.len() < 4 {
+                return Err(Error::OutOfSpec(
+                    "a snappy block must end with a 4-byte checksum".to_string(),
+                ));
+            }
             let crc = &block[block.len() - 4..];
             let block = &block[..block.len() - 4];
 
-            let len = snap::raw::decompress_len(block).map_err(|_| Error::OutOfSpec)?;
+            let len = snap::raw::decompress_len(block)
+                .map_err(|error| Error::OutOfSpec(error.to_string()))?;
             decompressed.clear();
             decompressed.resize(len, 0);
             snap::raw::Decoder::new()
                 .decompress(block, decompressed)
-                .map_err(|_| Error::OutOfSpec)?;
+                .map_err(|error| Error::OutOfSpec(error.to_string()))?;
 
             let expected_crc = u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]);
 
             let actual_crc = CRC_TABLE.checksum(decompressed);
             if expected_crc != actual_crc {
-                return Err(Error::OutOfSpec);
+                return Err(Error::ChecksumMismatch {
+                    expected: expected_crc,
+                    actual: actual_crc,
+                });
             }
             Ok(false)
         }
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstandard) => {
+            decompressed.clear();
+            zstd::stream::copy_decode(&block[..], decompressed)?;
+            Ok(false)
+        }
+        #[cfg(feature = "bzip2")]
+        Some(Compression::Bzip2) => {
+            decompressed.clear();
+            let mut decoder = bzip2::read::BzDecoder::new(&block[..]);
+            decoder.read_to_end(decompressed)?;
+            Ok(false)
+        }
+        #[cfg(feature = "xz2")]
+        Some(Compression::Xz) => {
+            decompressed.clear();
+            let mut decoder = xz2::read::XzDecoder::new(&block[..]);
+            decoder.read_to_end(decompressed)?;
+            Ok(false)
+        }
         #[cfg(not(feature = "compression"))]
         Some(Compression::Deflate) => Err(Error::RequiresCompression),
         #[cfg(not(feature = "compression"))]
         Some(Compression::Snappy) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "zstd"))]
+        Some(Compression::Zstandard) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "bzip2"))]
+        Some(Compression::Bzip2) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "xz2"))]
+        Some(Compression::Xz) => Err(Error::RequiresCompression),
     }
 }
 
+/// A byte range of a file skipped by the recovery mode of [`BlockStreamingIterator`]
+#[derive(Debug)]
+pub struct SkippedRange {
+    /// The position of the range in the file, see [`BlockStreamingIterator::with_position`]
+    pub offset: u64,
+    /// The number of bytes skipped, up to and including the next sync marker
+    pub len: u64,
+    /// The number of rows of the corrupt block, if its header could be read.
+    /// The range may contain more rows when the sync marker of the corrupt block is itself corrupt.
+    pub rows: Option<usize>,
+    /// The error that caused the range to be skipped
+    pub error: Error,
+}
+
 /// [`FallibleStreamingIterator`] of decompressed [`Block`]
 pub struct BlockStreamingIterator<R: Read> {
     blocks: CompressedBlockStreamingIterator<R>,
     compression: Option<Compression>,
     buf: Block,
     was_swapped: bool,
+    recovery: bool,
+    // the ranges skipped since the last call to `take_skipped`
+    skipped: Vec<SkippedRange>,
 }
 
 /// Returns a [`FallibleStreamingIterator`] of [`Block`].
@@ -88,14 +140,49 @@ impl<R: Read> BlockStreamingIterator<R> {
             compression,
             buf: Block::new(0, vec![]),
             was_swapped: false,
+            recovery: false,
+            skipped: vec![],
         }
     }
 
+    /// Sets the position of `reader` in the file (e.g. the length of the file's header), so that
+    /// the offsets reported in errors are positions in the file.
+    /// Otherwise, they are relative to the initial position of `reader`.
+    pub fn with_position(mut self, position: u64) -> Self {
+        self.blocks = self.blocks.with_position(position);
+        self
+    }
+
+    /// Enables the recovery mode: instead of erroring on a corrupt block (e.g. with a
+    /// mismatching sync marker, a wrong checksum or truncated data), the iterator scans forward
+    /// for the next occurrence of the file's marker and resumes from the block after it.
+    /// The skipped ranges are returned by [`Self::take_skipped`].
+    ///
+    /// Errors of the underlying reader are still returned.
+    pub fn with_recovery(mut self) -> Self {
+        self.recovery = true;
+        self
+    }
+
+    /// Returns the [`SkippedRange`]s of the recovery mode since the last call to this function
+    pub fn take_skipped(&mut self) -> Vec<SkippedRange> {
+        std::mem::take(&mut self.skipped)
+    }
+
     /// Deconstructs itself into its internal reader
     #[inline]
     pub fn into_inner(self) -> R {
         self.blocks.into_inner().0
     }
+
+    pub(crate) fn compressed_mut(&mut self) -> &mut CompressedBlockStreamingIterator<R> {
+        &mut self.blocks
+    }
+
+    /// Discards the current block, after the reader was moved to another one
+    pub(crate) fn discard(&mut self) {
+        self.buf.number_of_rows = 0;
+    }
 }
 
 impl<R: Read> FallibleStreamingIterator for BlockStreamingIterator<R> {
@@ -104,12 +191,64 @@ impl<R: Read> FallibleStreamingIterator for BlockStreamingIterator<R> {
 
     #[inline]
     fn advance(&mut self) -> Result<(), Error> {
-        if self.was_swapped {
-            std::mem::swap(&mut self.blocks.buffer().data, &mut self.buf.data);
+        loop {
+            if self.was_swapped {
+                std::mem::swap(&mut self.blocks.buffer().data, &mut self.buf.data);
+                self.was_swapped = false;
+            }
+
+            let error = match self.blocks.read_next() {
+                Ok(false) => {
+                    self.buf.number_of_rows = 0;
+                    return Ok(());
+                }
+                Ok(true) => {
+                    let block = self.blocks.buffer();
+                    let error = if block.number_of_rows == 0 {
+                        if block.data.is_empty() {
+                            continue;
+                        }
+                        rows_without_data(block.data.len())
+                    } else {
+                        match decompress_block(block, &mut self.buf, self.compression) {
+                            Ok(was_swapped) => {
+                                self.was_swapped = was_swapped;
+                                return Ok(());
+                            }
+                            Err(Error::RequiresCompression) => {
+                                return Err(Error::RequiresCompression)
+                            }
+                            Err(error) => error,
+                        }
+                    };
+                    let error = Error::CorruptBlock {
+                        block: self.blocks.block_index(),
+                        offset: self.blocks.block_offset(),
+                        error: Box::new(error),
+                    };
+                    if !self.recovery {
+                        return Err(error);
+                    }
+                    // the block was read up to its marker: the next block follows it
+                    self.buf.number_of_rows = 0;
+                    error
+                }
+                Err(Error::Io(error)) => return Err(Error::Io(error)),
+                Err(error) if self.recovery => {
+                    self.blocks.skip_to_marker()?;
+                    error
+                }
+                Err(error) => return Err(error),
+            };
+
+            let offset = self.blocks.block_offset();
+            self.skipped.push(SkippedRange {
+                offset,
+                len: self.blocks.offset() - offset,
+                rows: self.blocks.block_rows(),
+                error,
+            });
         }
-        self.blocks.advance()?;
-        self.was_swapped = decompress_block(self.blocks.buffer(), &mut self.buf, self.compression)?;
-        Ok(())
     }
 
     #[inline]
@@ -0,0 +1,321 @@
+//! APIs to deserialize Avro data into [`Value`]s.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Read;
+
+use crate::error::Error;
+use crate::file::Block;
+use crate::schema::{Record, Schema};
+use crate::value::Value;
+
+use super::decode::zigzag_i64;
+
+/// Deserializes Avro data described by a [`Schema`] into [`Value`]s.
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    schema: &'a Schema,
+    names: HashMap<String, &'a Schema>,
+    max_empty_items: u64,
+}
+
+impl<'a> Decoder<'a> {
+    /// Returns a new [`Decoder`] of `schema`.
+    pub fn new(schema: &'a Schema) -> Self {
+        Self {
+            schema,
+            names: schema.named_types(),
+            max_empty_items: MAX_EMPTY_ITEMS,
+        }
+    }
+
+    /// Sets the maximum number of items of an array whose items are encoded in zero bytes
+    /// (e.g. `null`), which defaults to 2^20.
+    ///
+    /// The number of such items is not bounded by the size of the data, so this bounds the memory
+    /// that decoding corrupt data can allocate.
+    pub fn with_max_empty_items(mut self, max_empty_items: u64) -> Self {
+        self.max_empty_items = max_empty_items;
+        self
+    }
+
+    /// Decodes a single datum from `reader`.
+    /// # Error
+    /// This function errors iff the data is not described by this decoder's schema, nests
+    /// records, arrays and maps more than 128 times, or has an array with more items encoded in
+    /// zero bytes than allowed by [`Self::with_max_empty_items`].
+    pub fn decode<R: Read>(&self, reader: &mut R) -> Result<Value, Error> {
+        self.decode_schema(reader, self.schema, 0)
+    }
+
+    /// Decodes all rows of `block` into `values`.
+    /// # Error
+    /// This function errors iff the block's data is not a sequence of `block.number_of_rows`
+    /// data that [`Self::decode`] reads.
+    pub fn decode_block(&self, block: &Block, values: &mut Vec<Value>) -> Result<(), Error> {
+        let mut data: &[u8] = block.data.as_ref();
+        values.reserve(block.number_of_rows);
+        for _ in 0..block.number_of_rows {
+            values.push(self.decode(&mut data)?);
+        }
+        if !data.is_empty() {
+            return Err(Error::OutOfSpec(format!(
+                "the block has {} bytes after its last row",
+                data.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decodes a datum of `schema`, nested in `depth` records, arrays or maps
+    // each arm is a separate function so that this frame, which recursive types repeat, is small
+    fn decode_schema<R: Read>(
+        &self,
+        reader: &mut R,
+        schema: &Schema,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        match schema {
+            Schema::Record(record) => self.decode_record(reader, record, nest(depth)?),
+            Schema::Array(item) => self.decode_array(reader, item, nest(depth)?),
+            Schema::Map(item) => self.decode_map(reader, item, nest(depth)?),
+            Schema::Union(schemas) => self.decode_union(reader, schemas, depth),
+            Schema::Ref(name) => self.decode_schema(reader, self.named(name)?, depth),
+            Schema::Annotated(schema, _) => self.decode_schema(reader, schema, depth),
+            _ => decode_leaf(reader, schema),
+        }
+    }
+
+    fn decode_record<R: Read>(
+        &self,
+        reader: &mut R,
+        record: &Record,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = Vec::with_capacity(record.fields.len());
+        for field in &record.fields {
+            let value = self.decode_schema(reader, &field.schema, depth)?;
+            values.push((field.name.clone(), value));
+        }
+        Ok(Value::Record(values))
+    }
+
+    fn decode_array<R: Read>(
+        &self,
+        reader: &mut R,
+        item: &Schema,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = vec![];
+        let max_items = self
+            .is_empty(item, &mut vec![])
+            .then_some(self.max_empty_items);
+        read_blocks(reader, max_items, |reader| {
+            values.push(self.decode_schema(reader, item, depth)?);
+            Ok(())
+        })?;
+        Ok(Value::Array(values))
+    }
+
+    fn decode_map<R: Read>(
+        &self,
+        reader: &mut R,
+        item: &Schema,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = HashMap::new();
+        // a map's items have a key, so they are never empty
+        read_blocks(reader, None, |reader| {
+            let key = read_string(reader)?;
+            values.insert(key, self.decode_schema(reader, item, depth)?);
+            Ok(())
+        })?;
+        Ok(Value::Map(values))
+    }
+
+    fn decode_union<R: Read>(
+        &self,
+        reader: &mut R,
+        schemas: &[Schema],
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let index = read_len(reader)?;
+        let schema = schemas.get(index).ok_or_else(|| {
+            Error::OutOfSpec(format!("the union has no variant with index {}", index))
+        })?;
+        let value = self.decode_schema(reader, schema, depth)?;
+        Ok(Value::Union(index, Box::new(value)))
+    }
+
+    fn named(&self, name: &str) -> Result<&'a Schema, Error> {
+        self.names
+            .get(name)
+            .copied()
+            .ok_or_else(|| Error::OutOfSpec(format!("the type \"{}\" is not defined", name)))
+    }
+
+    /// Whether data of `schema` is encoded in zero bytes, where `visiting` are the named types
+    /// being checked
+    fn is_empty<'b>(&'b self, schema: &'b Schema, visiting: &mut Vec<&'b str>) -> bool {
+        match schema {
+            Schema::Null(_) => true,
+            Schema::Record(record) => record
+                .fields
+                .iter()
+                .all(|field| self.is_empty(&field.schema, visiting)),
+            Schema::Fixed(fixed) => fixed.size == 0,
+            Schema::Ref(name) => {
+                // a type containing itself is never fully read
+                if visiting.contains(&name.as_str()) {
+                    return false;
+                }
+                visiting.push(name);
+                let is_empty = self
+                    .names
+                    .get(name)
+                    .is_some_and(|schema| self.is_empty(schema, visiting));
+                visiting.pop();
+                is_empty
+            }
+            Schema::Annotated(schema, _) => self.is_empty(schema, visiting),
+            _ => false,
+        }
+    }
+}
+
+/// Decodes a datum of `schema`, which contains no other data
+fn decode_leaf<R: Read>(reader: &mut R, schema: &Schema) -> Result<Value, Error> {
+    Ok(match schema {
+        Schema::Null(_) => Value::Null,
+        Schema::Boolean(_) => Value::Boolean(read_boolean(reader)?),
+        Schema::Int(_) => Value::Int(read_int(reader)?),
+        Schema::Long(_) => Value::Long(zigzag_i64(reader)?),
+        Schema::Float(_) => Value::Float(read_float(reader)?),
+        Schema::Double(_) => Value::Double(read_double(reader)?),
+        Schema::Bytes(_) => Value::Bytes(read_bytes(reader)?),
+        Schema::String(_) => Value::String(read_string(reader)?),
+        Schema::Enum(enum_) => {
+            let index = read_len(reader)?;
+            let symbol = enum_.symbols.get(index).ok_or_else(|| {
+                Error::OutOfSpec(format!(
+                    "the enum \"{}\" has no symbol with index {}",
+                    enum_.name, index
+                ))
+            })?;
+            Value::Enum(symbol.clone())
+        }
+        Schema::Fixed(fixed) => Value::Fixed(read_exact(reader, fixed.size)?),
+        _ => unreachable!("only called with schemas without children"),
+    })
+}
+
+/// Returns the depth of data nested in a record, array or map at `depth`
+pub(crate) fn nest(depth: usize) -> Result<usize, Error> {
+    if depth >= MAX_DEPTH {
+        return Err(Error::OutOfSpec(format!(
+            "records, arrays and maps can be nested at most {} times",
+            MAX_DEPTH
+        )));
+    }
+    Ok(depth + 1)
+}
+
+pub(crate) fn read_boolean<R: Read>(reader: &mut R) -> Result<bool, Error> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    match byte[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => Err(Error::OutOfSpec(format!(
+            "a boolean must be 0 or 1, found {}",
+            other
+        ))),
+    }
+}
+
+pub(crate) fn read_int<R: Read>(reader: &mut R) -> Result<i32, Error> {
+    let value = zigzag_i64(reader)?;
+    i32::try_from(value)
+        .map_err(|_| Error::OutOfSpec(format!("the int {} does not fit in 32 bits", value)))
+}
+
+pub(crate) fn read_float<R: Read>(reader: &mut R) -> Result<f32, Error> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_double<R: Read>(reader: &mut R) -> Result<f64, Error> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_len<R: Read>(reader: &mut R) -> Result<usize, Error> {
+    let len = zigzag_i64(reader)?;
+    usize::try_from(len)
+        .map_err(|_| Error::OutOfSpec(format!("a length must be non-negative, found {}", len)))
+}
+
+pub(crate) fn read_exact<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![];
+    bytes
+        .try_reserve(len)
+        .map_err(|_| Error::OutOfSpec(format!("cannot allocate {} bytes", len)))?;
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(bytes)
+}
+
+pub(crate) fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>, Error> {
+    let len = read_len(reader)?;
+    read_exact(reader, len)
+}
+
+pub(crate) fn read_string<R: Read>(reader: &mut R) -> Result<String, Error> {
+    String::from_utf8(read_bytes(reader)?)
+        .map_err(|error| Error::OutOfSpec(format!("strings must be utf8: {}", error)))
+}
+
+/// The default maximum number of items of an array whose items are encoded in zero bytes.
+pub(crate) const MAX_EMPTY_ITEMS: u64 = 1 << 20;
+
+/// The maximum nesting of records, arrays and maps, e.g. of a record in an array in a record.
+/// Recursive types can nest data without bound, so corrupt data would otherwise overflow the
+/// stack.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// Reads the items of an array or map, encoded as a series of blocks, calling `read_item` once per item.
+/// `max_items` is the maximum number of items, if any.
+pub(crate) fn read_blocks<R, F>(
+    reader: &mut R,
+    max_items: Option<u64>,
+    mut read_item: F,
+) -> Result<(), Error>
+where
+    R: Read,
+    F: FnMut(&mut R) -> Result<(), Error>,
+{
+    let mut items = 0u64;
+    loop {
+        let count = zigzag_i64(reader)?;
+        if count == 0 {
+            return Ok(());
+        }
+        if count < 0 {
+            // a negative count is followed by the block's size in bytes, which we do not need
+            let _ = zigzag_i64(reader)?;
+        }
+        items = items.saturating_add(count.unsigned_abs());
+        if let Some(max_items) = max_items.filter(|max_items| items > *max_items) {
+            return Err(Error::OutOfSpec(format!(
+                "an array of items encoded in zero bytes can have at most {} items, found {}",
+                max_items, items
+            )));
+        }
+        for _ in 0..count.unsigned_abs() {
+            read_item(reader)?;
+        }
+    }
+}
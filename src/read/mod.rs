@@ -1,13 +1,16 @@
 //! Functions to read and decompress Files' metadata and blocks
 mod block;
-mod decode;
+pub(crate) mod decode;
 pub(crate) mod decompress;
+pub(crate) mod deserialize;
+mod parallel;
+mod seek;
+mod split;
 
 use std::io::Read;
 
 use crate::error::Error;
 use crate::file::FileMetadata;
-use crate::schema::Schema;
 
 pub use fallible_streaming_iterator;
 
@@ -42,7 +45,7 @@ macro_rules! read_header {
         let mut items = HashMap::new();
 
         loop {
-            let len = zigzag_i64($reader)$($_await)*.map_err(|_| Error::OutOfSpec)? as usize;
+            let len = zigzag_i64($reader)$($_await)*? as usize;
             if len == 0 {
                 break Ok(items);
             }
@@ -51,7 +54,7 @@ macro_rules! read_header {
             for _ in 0..len {
                 let key = _read_binary($reader)$($_await)*?;
                 let key = String::from_utf8(key)
-                    .map_err(|_| Error::OutOfSpec)?;
+                    .map_err(|_| Error::OutOfSpec("header keys must be utf8".to_string()))?;
                 let value = _read_binary($reader)$($_await)*?;
                 items.insert(key, value);
             }
@@ -62,36 +65,35 @@ macro_rules! read_header {
 macro_rules! read_metadata_macro {
     ($reader:ident $($_await:tt)*) => {{
         let mut magic_number = [0u8; 4];
-        $reader.read_exact(&mut magic_number)$($_await)*.map_err(|_| Error::OutOfSpec)?;
+        $reader.read_exact(&mut magic_number)$($_await)*?;
 
         // see https://avro.apache.org/docs/current/spec.html#Object+Container+Files
         if magic_number != [b'O', b'b', b'j', 1u8] {
-            return Err(Error::OutOfSpec);
+            return Err(Error::InvalidMagic(magic_number));
         }
 
         let header = decode::read_header($reader)$($_await)*?;
 
-        let (schema, compression) = deserialize_header(header)?;
+        let (schema, compression) = deserialize_header(&header)?;
 
         let marker = decode::read_file_marker($reader)$($_await)*?;
 
-        let record = if let Schema::Record(record) = schema {
-            record
-        } else {
-            return Err(Error::OutOfSpec)
-        };
-
         Ok(FileMetadata {
-            record,
+            schema,
             compression,
             marker,
+            metadata: header.into_iter().collect(),
         })
     }};
 }
 
 #[allow(unused_imports)]
 pub(crate) use {
-    avro_decode, decode::deserialize_header, decode::DecodeError, read_header, read_metadata_macro,
+    avro_decode,
+    block::{block_len, block_size, rows_without_data, truncated},
+    decode::deserialize_header,
+    decode::DecodeError,
+    read_header, read_metadata_macro,
 };
 
 /// Reads the metadata from `reader` into [`FileMetadata`].
@@ -101,4 +103,8 @@ pub fn read_metadata<R: Read>(reader: &mut R) -> Result<FileMetadata, Error> {
     read_metadata_macro!(reader)
 }
 
-pub use decompress::{block_iterator, BlockStreamingIterator};
+pub use decompress::{block_iterator, BlockStreamingIterator, SkippedRange};
+pub use deserialize::Decoder;
+pub use parallel::ParallelBlockStreamingIterator;
+pub use seek::{BlockLocation, SeekableBlockStreamingIterator};
+pub use split::SplitBlockStreamingIterator;
@@ -0,0 +1,224 @@
+//! APIs to decompress the blocks of a file in parallel.
+use std::io::Read;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread::JoinHandle;
+
+use fallible_streaming_iterator::FallibleStreamingIterator;
+
+use crate::error::Error;
+use crate::file::{Block, CompressedBlock, Compression};
+
+use super::block::CompressedBlockStreamingIterator;
+use super::decompress::decompress_block;
+
+/// [`FallibleStreamingIterator`] of decompressed [`Block`] that reads the [`CompressedBlock`]s
+/// of a file on one thread and decompresses them on a pool of worker threads.
+///
+/// Blocks are distributed to the workers in turns and collected in the same order, so they are
+/// yielded in the order of the file. Besides the block being read and the block yielded, each
+/// worker holds at most three blocks: one waiting to be decompressed, one being decompressed
+/// and one decompressed block waiting to be yielded, which bounds the memory used.
+/// The buffers of yielded blocks are returned to the workers, and those of decompressed blocks
+/// to the reading thread, so that they are reused instead of allocated for every block; at most
+/// two spare buffers per worker are kept.
+pub struct ParallelBlockStreamingIterator {
+    // starts the thread reading the file at a position, taken by the first call to `advance`
+    start: Option<Box<dyn FnOnce(u64) -> JoinHandle<()> + Send>>,
+    position: u64,
+    results: Vec<Receiver<Result<Block, Error>>>,
+    // returns the buffers of yielded blocks to each worker
+    buffers: Vec<SyncSender<Vec<u8>>>,
+    // the worker of the next block
+    next: usize,
+    threads: Vec<JoinHandle<()>>,
+    buf: Block,
+}
+
+impl ParallelBlockStreamingIterator {
+    /// Returns a new [`ParallelBlockStreamingIterator`] reading blocks from `reader`, positioned
+    /// at the first block of the file, and decompressing them on `workers` threads (at least 1).
+    pub fn new<R: Read + Send + 'static>(
+        reader: R,
+        compression: Option<Compression>,
+        marker: [u8; 16],
+        workers: usize,
+    ) -> Self {
+        let workers = workers.max(1);
+
+        let mut threads = Vec::with_capacity(workers + 1);
+        let mut blocks = Vec::with_capacity(workers);
+        let mut results = Vec::with_capacity(workers);
+        let mut buffers = Vec::with_capacity(workers);
+        let (spare_sender, spare_receiver) = sync_channel(workers);
+        for _ in 0..workers {
+            let (block_sender, block_receiver) = sync_channel(1);
+            let (result_sender, result_receiver) = sync_channel(1);
+            let (buffer_sender, buffer_receiver) = sync_channel(1);
+            let spare_sender = spare_sender.clone();
+            threads.push(std::thread::spawn(move || {
+                decompress(
+                    block_receiver,
+                    result_sender,
+                    buffer_receiver,
+                    spare_sender,
+                    compression,
+                )
+            }));
+            blocks.push(block_sender);
+            results.push(result_receiver);
+            buffers.push(buffer_sender);
+        }
+        let start = Box::new(move |position| {
+            std::thread::spawn(move || read(reader, marker, position, blocks, spare_receiver))
+        });
+
+        Self {
+            start: Some(start),
+            position: 0,
+            results,
+            buffers,
+            next: 0,
+            threads,
+            buf: Block::default(),
+        }
+    }
+
+    /// Sets the position of `reader` in the file, as
+    /// [`BlockStreamingIterator::with_position`](super::BlockStreamingIterator::with_position)
+    /// does.
+    pub fn with_position(mut self, position: u64) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Waits for all threads to finish, erroring iff one of them panicked.
+    fn join(&mut self) -> Result<(), Error> {
+        // dropping the senders and receivers stops the threads that are still running
+        self.start = None;
+        self.results.clear();
+        let mut result = Ok(());
+        for thread in self.threads.drain(..) {
+            if thread.join().is_err() {
+                result = Err(Error::Io(std::io::Error::other(
+                    "a thread reading the file panicked",
+                )));
+            }
+        }
+        result
+    }
+}
+
+/// A block to decompress, with its index and offset in the file
+type Task = (usize, u64, CompressedBlock);
+
+/// Reads the blocks of `reader`, at `position` in the file, sending them to each of `workers`
+/// in turns and reading into the buffers received from `spare`
+fn read<R: Read>(
+    reader: R,
+    marker: [u8; 16],
+    position: u64,
+    workers: Vec<SyncSender<Result<Task, Error>>>,
+    spare: Receiver<Vec<u8>>,
+) {
+    let mut blocks =
+        CompressedBlockStreamingIterator::new(reader, marker, vec![]).with_position(position);
+    for worker in workers.iter().cycle() {
+        let block = match blocks.advance() {
+            Ok(()) if blocks.buffer().number_of_rows == 0 => return,
+            Ok(()) => {
+                let (index, offset) = (blocks.block_index(), blocks.block_offset());
+                let block = blocks.buffer();
+                let buffer = spare.try_recv().unwrap_or_default();
+                let data = std::mem::replace(&mut block.data, buffer);
+                Ok((
+                    index,
+                    offset,
+                    CompressedBlock::new(block.number_of_rows, data),
+                ))
+            }
+            Err(error) => Err(error),
+        };
+        let is_error = block.is_err();
+        // stop after an error or once the iterator is dropped
+        if worker.send(block).is_err() || is_error {
+            return;
+        }
+    }
+}
+
+/// Decompresses the blocks received from `blocks` into the buffers received from `buffers`,
+/// sending them to `results` and the buffers of the compressed blocks to `spare`
+fn decompress(
+    blocks: Receiver<Result<Task, Error>>,
+    results: SyncSender<Result<Block, Error>>,
+    buffers: Receiver<Vec<u8>>,
+    spare: SyncSender<Vec<u8>>,
+    compression: Option<Compression>,
+) {
+    for block in blocks {
+        let block = block.and_then(|(index, offset, mut block)| {
+            let mut decompressed = Block::new(0, buffers.try_recv().unwrap_or_default());
+            let result = decompress_block(&mut block, &mut decompressed, compression);
+            // the buffer is dropped when the reading thread already has enough spare ones
+            let _ = spare.try_send(std::mem::take(&mut block.data));
+            match result {
+                Ok(_) => Ok(decompressed),
+                Err(Error::RequiresCompression) => Err(Error::RequiresCompression),
+                Err(error) => Err(Error::CorruptBlock {
+                    block: index,
+                    offset,
+                    error: Box::new(error),
+                }),
+            }
+        });
+        if results.send(block).is_err() {
+            return;
+        }
+    }
+}
+
+impl FallibleStreamingIterator for ParallelBlockStreamingIterator {
+    type Error = Error;
+    type Item = Block;
+
+    fn advance(&mut self) -> Result<(), Error> {
+        if let Some(start) = self.start.take() {
+            self.threads.push(start(self.position));
+        }
+        self.buf.number_of_rows = 0;
+        let result = match self.results.get(self.next) {
+            Some(results) => results.recv(),
+            None => return Ok(()),
+        };
+        let worker = self.next;
+        self.next = (self.next + 1) % self.results.len();
+        match result {
+            Ok(Ok(block)) => {
+                let previous = std::mem::replace(&mut self.buf, block);
+                // the worker is not waiting for a buffer when it already has one
+                let _ = self.buffers[worker].try_send(previous.data);
+                Ok(())
+            }
+            Ok(Err(error)) => {
+                let _ = self.join();
+                Err(error)
+            }
+            // the worker finished: there are no more blocks
+            Err(_) => self.join(),
+        }
+    }
+
+    fn get(&self) -> Option<&Self::Item> {
+        if self.buf.number_of_rows > 0 {
+            Some(&self.buf)
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for ParallelBlockStreamingIterator {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
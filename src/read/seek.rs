@@ -0,0 +1,164 @@
+//! APIs to read blocks of a file out of order.
+use std::io::{Read, Seek, SeekFrom};
+
+use fallible_streaming_iterator::FallibleStreamingIterator;
+
+use crate::error::Error;
+use crate::file::{Block, Compression};
+
+use super::block::{block_len, read_size};
+use super::decompress::BlockStreamingIterator;
+
+/// The location of a block in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockLocation {
+    /// The position of the block in the reader
+    pub offset: u64,
+    /// The index of the block's first row in the file
+    pub first_row: usize,
+    /// The number of rows of the block
+    pub number_of_rows: usize,
+}
+
+/// [`FallibleStreamingIterator`] of decompressed [`Block`] over a [`Read`] + [`Seek`] that can
+/// jump to any block or row of the file.
+///
+/// It lazily builds an index of the blocks of the file: each block is located by reading its
+/// header, skipping its data and checking that it is followed by the file's sync marker.
+/// The data of the blocks that are jumped over is never read.
+pub struct SeekableBlockStreamingIterator<R: Read + Seek> {
+    blocks: BlockStreamingIterator<R>,
+    marker: [u8; 16],
+    index: Vec<BlockLocation>,
+    // the position right after the last indexed block
+    end: u64,
+    // whether `index` contains every block of the file
+    complete: bool,
+}
+
+impl<R: Read + Seek> SeekableBlockStreamingIterator<R> {
+    /// Returns a new [`SeekableBlockStreamingIterator`] from a `reader` positioned at the first
+    /// block of the file, e.g. right after [`read_metadata`](super::read_metadata).
+    pub fn try_new(
+        mut reader: R,
+        compression: Option<Compression>,
+        marker: [u8; 16],
+    ) -> Result<Self, Error> {
+        let start = reader.stream_position()?;
+        Ok(Self {
+            blocks: BlockStreamingIterator::new(reader, compression, marker).with_position(start),
+            marker,
+            index: vec![],
+            end: start,
+            complete: false,
+        })
+    }
+
+    /// Returns the location of every block of the file, indexing the blocks that were not
+    /// indexed yet. The position of the iterator is unchanged.
+    pub fn index(&mut self) -> Result<&[BlockLocation], Error> {
+        let reader = self.blocks.compressed_mut().reader_mut();
+        let position = reader.stream_position()?;
+        while self.index_next()? {}
+        let reader = self.blocks.compressed_mut().reader_mut();
+        reader.seek(SeekFrom::Start(position))?;
+        Ok(&self.index)
+    }
+
+    /// Moves the iterator to the `block`-th block of the file, so that the next call to
+    /// [`next`](FallibleStreamingIterator::next) returns it.
+    /// When the file has fewer blocks, the iterator is moved to the end of the file.
+    pub fn seek_to_block(&mut self, block: usize) -> Result<(), Error> {
+        while self.index.len() <= block && self.index_next()? {}
+        let (block, offset) = match self.index.get(block) {
+            Some(location) => (block, location.offset),
+            None => (self.index.len(), self.end),
+        };
+        let blocks = self.blocks.compressed_mut();
+        blocks.reader_mut().seek(SeekFrom::Start(offset))?;
+        blocks.set_position(block, offset);
+        self.blocks.discard();
+        Ok(())
+    }
+
+    /// Moves the iterator to the block containing the `row`-th row of the file, so that the
+    /// next call to [`next`](FallibleStreamingIterator::next) returns it, and returns the
+    /// index of the row within that block.
+    /// When the file has fewer rows, the iterator is moved to the end of the file.
+    pub fn seek_to_row(&mut self, row: usize) -> Result<usize, Error> {
+        while self.rows() <= row && self.index_next()? {}
+        // the block containing `row` is the last one starting at or before it
+        let block = self.index.partition_point(|x| x.first_row <= row);
+        if row >= self.rows() || block == 0 {
+            self.seek_to_block(self.index.len())?;
+            return Ok(0);
+        }
+        let first_row = self.index[block - 1].first_row;
+        self.seek_to_block(block - 1)?;
+        Ok(row - first_row)
+    }
+
+    /// Deconstructs itself into its internal reader
+    pub fn into_inner(self) -> R {
+        self.blocks.into_inner()
+    }
+
+    /// The number of rows of the indexed blocks
+    fn rows(&self) -> usize {
+        self.index
+            .last()
+            .map(|x| x.first_row + x.number_of_rows)
+            .unwrap_or(0)
+    }
+
+    /// Indexes the next block of the file, returning whether there was one
+    fn index_next(&mut self) -> Result<bool, Error> {
+        if self.complete {
+            return Ok(false);
+        }
+        let reader = self.blocks.compressed_mut().reader_mut();
+        reader.seek(SeekFrom::Start(self.end))?;
+        let (rows, bytes) = match read_size(reader, self.index.len(), self.end)? {
+            Some(size) => size,
+            None => {
+                self.complete = true;
+                return Ok(false);
+            }
+        };
+        reader.seek(SeekFrom::Current(bytes as i64))?;
+        let mut marker = [0u8; 16];
+        reader.read_exact(&mut marker)?;
+        if marker != self.marker {
+            return Err(Error::SyncMarkerMismatch {
+                block: self.index.len(),
+                offset: self.end,
+                expected: self.marker,
+                actual: marker,
+            });
+        }
+
+        let location = BlockLocation {
+            offset: self.end,
+            first_row: self.rows(),
+            number_of_rows: rows,
+        };
+        self.index.push(location);
+        self.end += block_len(rows, bytes);
+        Ok(true)
+    }
+}
+
+impl<R: Read + Seek> FallibleStreamingIterator for SeekableBlockStreamingIterator<R> {
+    type Error = Error;
+    type Item = Block;
+
+    #[inline]
+    fn advance(&mut self) -> Result<(), Error> {
+        self.blocks.advance()
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&Self::Item> {
+        self.blocks.get()
+    }
+}
@@ -0,0 +1,117 @@
+//! APIs to read a byte range of a file, so that a file can be read by multiple workers.
+use std::io::{BufRead, Read, Seek, SeekFrom};
+
+use fallible_streaming_iterator::FallibleStreamingIterator;
+
+use crate::error::Error;
+use crate::file::{Block, FileMetadata};
+
+use super::decompress::BlockStreamingIterator;
+
+/// [`FallibleStreamingIterator`] of the decompressed [`Block`]s of a file that start within a
+/// byte range of it (a split).
+///
+/// The iterator syncs to the first sync marker ending at or after the start of the range and
+/// yields every block that starts before the end of the range.
+/// Each block starts within exactly one range, so splits that partition the file
+/// (e.g. `(0, n)`, `(n, n)`, `(2n, n)`, ...) read every block of the file exactly once.
+pub struct SplitBlockStreamingIterator<R: Read> {
+    blocks: BlockStreamingIterator<R>,
+    // the end of the range
+    end: u64,
+    finished: bool,
+}
+
+impl<R: BufRead + Seek> SplitBlockStreamingIterator<R> {
+    /// Returns a new [`SplitBlockStreamingIterator`] over the blocks of the file in `reader`
+    /// starting within the `len` bytes after `start`, where `metadata` is the file's metadata.
+    pub fn try_new(
+        mut reader: R,
+        metadata: &FileMetadata,
+        start: u64,
+        len: u64,
+    ) -> Result<Self, Error> {
+        let end = start.saturating_add(len);
+
+        // a block starts right after a marker, which may end exactly at `start`
+        let sync = start.saturating_sub(16);
+        reader.seek(SeekFrom::Start(sync))?;
+        let (start, finished) = match sync_to_marker(&mut reader, metadata.marker)? {
+            Some(read) => (sync + read, sync + read >= end),
+            None => (sync, true),
+        };
+
+        Ok(Self {
+            blocks: BlockStreamingIterator::new(reader, metadata.compression, metadata.marker)
+                .with_position(start),
+            end,
+            finished,
+        })
+    }
+}
+
+/// Consumes `reader` up to and including the next occurrence of `marker`, returning the number
+/// of bytes consumed, or `None` if `reader` ends before it.
+fn sync_to_marker<R: BufRead>(reader: &mut R, marker: [u8; 16]) -> Result<Option<u64>, Error> {
+    let mut window = [0u8; 16];
+    let mut read = 0u64;
+    loop {
+        let buffer = match reader.fill_buf() {
+            Ok(buffer) => buffer,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+        let mut consumed = 0;
+        let mut found = false;
+        for byte in buffer {
+            window.copy_within(1.., 0);
+            window[15] = *byte;
+            consumed += 1;
+            read += 1;
+            if read >= 16 && window == marker {
+                found = true;
+                break;
+            }
+        }
+        reader.consume(consumed);
+        if found {
+            return Ok(Some(read));
+        }
+    }
+}
+
+impl<R: Read> SplitBlockStreamingIterator<R> {
+    /// Deconstructs itself into its internal reader
+    pub fn into_inner(self) -> R {
+        self.blocks.into_inner()
+    }
+}
+
+impl<R: Read> FallibleStreamingIterator for SplitBlockStreamingIterator<R> {
+    type Error = Error;
+    type Item = Block;
+
+    fn advance(&mut self) -> Result<(), Error> {
+        if !self.finished && self.blocks.compressed_mut().offset() >= self.end {
+            self.finished = true;
+        }
+        if self.finished {
+            self.blocks.discard();
+            return Ok(());
+        }
+        self.blocks.advance()?;
+        // blocks without rows are passed over, so the block read may start after the range
+        if self.blocks.compressed_mut().block_offset() >= self.end {
+            self.finished = true;
+            self.blocks.discard();
+        }
+        Ok(())
+    }
+
+    fn get(&self) -> Option<&Self::Item> {
+        self.blocks.get()
+    }
+}
@@ -6,53 +6,73 @@ use futures::Stream;
 
 use crate::error::Error;
 use crate::file::CompressedBlock;
-use crate::read::DecodeError;
+use crate::read::{block_len, block_size, rows_without_data, truncated, DecodeError};
 
 use super::decode::zigzag_i64;
 
-async fn read_size<R: AsyncRead + Unpin + Send>(reader: &mut R) -> Result<(usize, usize), Error> {
+/// Reads the number of rows and bytes of the `index`-th block, at `offset`, or `None` at the
+/// end of the file
+async fn read_size<R: AsyncRead + Unpin + Send>(
+    reader: &mut R,
+    index: usize,
+    offset: u64,
+) -> Result<Option<(usize, usize)>, Error> {
     let rows = match zigzag_i64(reader).await {
         Ok(a) => a,
         Err(error) => match error {
-            DecodeError::EndOfFile => return Ok((0, 0)),
-            DecodeError::OutOfSpec => return Err(Error::OutOfSpec),
+            DecodeError::EndOfFile => return Ok(None),
+            error => return Err(error.into()),
         },
     };
-
-    let bytes = zigzag_i64(reader).await?;
-    Ok((rows as usize, bytes as usize))
+    let bytes = match zigzag_i64(reader).await {
+        Ok(a) => a,
+        Err(error) => match error {
+            DecodeError::EndOfFile => return Err(truncated(index, offset)),
+            error => return Err(error.into()),
+        },
+    };
+    block_size(rows, bytes).map(Some)
 }
 
-/// Reads a [`CompressedBlock`] from the `reader`.
+/// Reads the data and sync marker of a [`CompressedBlock`] of `bytes` bytes from the `reader`.
+/// `index` and `offset` are the position of the block, used to report errors.
 /// # Error
 /// This function errors iff either the block cannot be read or the sync marker does not match
 async fn read_block<R: AsyncRead + Unpin + Send>(
     reader: &mut R,
     block: &mut CompressedBlock,
+    bytes: usize,
     marker: [u8; 16],
+    index: usize,
+    offset: u64,
 ) -> Result<(), Error> {
-    let (rows, bytes) = read_size(reader).await?;
-    block.number_of_rows = rows;
-    if rows == 0 {
-        return Ok(());
-    };
-
     block.data.clear();
-    block
-        .data
-        .try_reserve(bytes)
-        .map_err(|_| Error::OutOfSpec)?;
+    // the data is read together with the marker that follows it
+    block.data.try_reserve(bytes + 16).map_err(|_| {
+        Error::OutOfSpec(format!(
+            "cannot allocate {} bytes for block {} (at offset {})",
+            bytes, index, offset
+        ))
+    })?;
     reader
-        .take(bytes as u64)
+        .take(bytes as u64 + 16)
         .read_to_end(&mut block.data)
         .await?;
+    if block.data.len() < bytes + 16 {
+        return Err(truncated(index, offset));
+    }
 
-    let mut block_marker = [0u8; 16];
-    reader.read_exact(&mut block_marker).await?;
-
-    if block_marker != marker {
-        return Err(Error::OutOfSpec);
+    if block.data[bytes..] != marker {
+        let mut actual = [0u8; 16];
+        actual.copy_from_slice(&block.data[bytes..]);
+        return Err(Error::SyncMarkerMismatch {
+            block: index,
+            offset,
+            expected: marker,
+            actual,
+        });
     }
+    block.data.truncate(bytes);
     Ok(())
 }
 
@@ -62,13 +82,27 @@ pub async fn block_stream<R: AsyncRead + Unpin + Send>(
     marker: [u8; 16],
 ) -> impl Stream<Item = Result<CompressedBlock, Error>> + '_ {
     try_stream! {
+        let mut index = 0;
+        let mut offset = 0;
         loop {
-            let mut block = CompressedBlock::new(0, vec![]);
-            read_block(reader, &mut block, marker).await?;
-            if block.number_of_rows == 0 {
-                break
+            let (rows, bytes) = match read_size(reader, index, offset).await? {
+                Some(size) => size,
+                None => break,
+            };
+            let mut block = CompressedBlock::new(rows, vec![]);
+            read_block(reader, &mut block, bytes, marker, index, offset).await?;
+            if rows == 0 && bytes > 0 {
+                Err(Error::CorruptBlock {
+                    block: index,
+                    offset,
+                    error: Box::new(rows_without_data(bytes)),
+                })?;
+            }
+            index += 1;
+            offset += block_len(rows, bytes);
+            if rows > 0 {
+                yield block
             }
-            yield block
         }
     }
 }
@@ -32,7 +32,8 @@ pub(crate) async fn read_file_marker<R: AsyncRead + Unpin + Send>(
 async fn _read_binary<R: AsyncRead + Unpin + Send>(reader: &mut R) -> Result<Vec<u8>, Error> {
     let len: usize = zigzag_i64(reader).await? as usize;
     let mut buf = vec![];
-    buf.try_reserve(len).map_err(|_| Error::OutOfSpec)?;
+    buf.try_reserve(len)
+        .map_err(|_| Error::OutOfSpec(format!("cannot allocate {} bytes", len)))?;
     reader.take(len as u64).read_to_end(&mut buf).await?;
     Ok(buf)
 }
@@ -0,0 +1,749 @@
+//! APIs to read data written with a schema (the writer's) as data of another schema (the reader's),
+//! following the spec's [schema resolution](https://avro.apache.org/docs/current/spec.html#Schema+Resolution).
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::error::Error;
+use crate::file::Block;
+use crate::read::decode::zigzag_i64;
+use crate::read::deserialize::{
+    nest, read_blocks, read_boolean, read_bytes, read_double, read_exact, read_float, read_int,
+    read_len, read_string, MAX_EMPTY_ITEMS,
+};
+use crate::schema::{join_path, Field, Schema};
+use crate::value::{from_default, Value};
+
+/// An incompatibility between a writer's and a reader's schema
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Incompatibility {
+    /// The location of the incompatibility, e.g. `User.address.zip`
+    pub path: String,
+    /// A description of the incompatibility
+    pub reason: String,
+}
+
+impl std::fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// A promotion of a writer's type to a reader's type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Promotion {
+    IntToLong,
+    IntToFloat,
+    IntToDouble,
+    LongToFloat,
+    LongToDouble,
+    FloatToDouble,
+    StringToBytes,
+    BytesToString,
+}
+
+/// How a field of the writer's record is read
+#[derive(Debug, Clone, PartialEq)]
+enum ResolvedField {
+    /// Read as the reader's field at this position
+    Read(usize, Resolved),
+    /// Read and discarded, as the reader has no such field
+    Skip(Resolved),
+}
+
+/// How a writer's type is read as a reader's type
+#[derive(Debug, Clone, PartialEq)]
+enum Resolved {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Promote(Promotion),
+    Record {
+        /// the names of the reader's fields
+        names: Vec<String>,
+        /// the writer's fields, in the order they are written
+        fields: Vec<ResolvedField>,
+        /// the positions and values of the reader's fields missing from the writer's
+        defaults: Vec<(usize, Value)>,
+    },
+    /// The reader's symbol of each of the writer's symbols
+    Enum(Vec<Result<String, Incompatibility>>),
+    Array(Box<Resolved>),
+    Map(Box<Resolved>),
+    /// A union of the writer, with the resolution of each of its variants
+    Union(Vec<Resolved>),
+    /// A non-union of the writer read as this variant of the reader's union
+    ToUnion(usize, Box<Resolved>),
+    Fixed(usize),
+    /// A named type, resolved in `Resolution::named`
+    Named(usize),
+    /// A type of the writer that the reader cannot read; it errors when data of it is read
+    Incompatible(Incompatibility),
+}
+
+/// A plan to read data written with a writer's schema as data of a reader's schema,
+/// returned by [`resolve`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    root: Resolved,
+    named: Vec<Resolved>,
+    max_empty_items: u64,
+}
+
+/// Resolves the writer's schema (e.g. from [`FileMetadata::record`](crate::file::FileMetadata))
+/// against the reader's schema.
+///
+/// Following the spec, a variant of the writer's union or a symbol of the writer's enum that
+/// the reader cannot read only errors when data of it is read.
+/// # Error
+/// This function errors with [`Error::IncompatibleSchemas`] and every incompatibility found
+/// when data of the writer cannot be read with the reader's schema.
+pub fn resolve(writer: &Schema, reader: &Schema) -> Result<Resolution, Error> {
+    let mut resolver = Resolver::new(writer, reader);
+    let root = resolver.resolve(writer, reader);
+    match root {
+        Some(root) if resolver.errors.is_empty() => Ok(Resolution {
+            root,
+            named: resolver.named,
+            max_empty_items: MAX_EMPTY_ITEMS,
+        }),
+        _ => Err(Error::IncompatibleSchemas(resolver.errors)),
+    }
+}
+
+/// Returns every incompatibility between the writer's and the reader's schema, including those of
+/// variants of the writer's unions and symbols of the writer's enums that [`resolve`] only reports
+/// when data of them is read.
+/// The schemas are compatible, i.e. any data of the writer can be read by the reader, iff none is returned.
+pub fn incompatibilities(writer: &Schema, reader: &Schema) -> Vec<Incompatibility> {
+    let mut resolver = Resolver::new(writer, reader);
+    resolver.resolve(writer, reader);
+    let mut incompatibilities = resolver.errors;
+    incompatibilities.extend(resolver.deferred);
+    incompatibilities
+}
+
+struct Resolver<'a> {
+    writer_names: HashMap<String, &'a Schema>,
+    reader_names: HashMap<String, &'a Schema>,
+    named: Vec<Resolved>,
+    /// the position in `named` of each pair of (writer, reader) named types resolved so far
+    indices: HashMap<(*const Schema, *const Schema), usize>,
+    path: Vec<String>,
+    /// incompatibilities that prevent any data from being read
+    errors: Vec<Incompatibility>,
+    /// incompatibilities that only error when data of them is read
+    deferred: Vec<Incompatibility>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(writer: &'a Schema, reader: &'a Schema) -> Self {
+        Self {
+            writer_names: writer.named_types(),
+            reader_names: reader.named_types(),
+            named: vec![],
+            indices: HashMap::new(),
+            path: vec![writer.type_name().to_string()],
+            errors: vec![],
+            deferred: vec![],
+        }
+    }
+
+    /// Records an incompatibility at the current path that prevents any data from being read
+    fn error(&mut self, reason: String) {
+        self.errors.push(Incompatibility {
+            path: join_path(&self.path),
+            reason,
+        });
+    }
+
+    /// Resolves `writer` against `reader`, returning `None` and recording the
+    /// incompatibilities in `self.errors` when they are not compatible.
+    fn resolve(&mut self, writer: &'a Schema, reader: &'a Schema) -> Option<Resolved> {
+        let writer = deref(writer, &self.writer_names);
+        let reader = deref(reader, &self.reader_names);
+        let (writer, reader) = match (writer, reader) {
+            (Ok(writer), Ok(reader)) => (writer, reader),
+            (Err(name), _) | (_, Err(name)) => {
+                self.error(format!("the type \"{}\" is not defined", name));
+                return None;
+            }
+        };
+
+        use Promotion::*;
+        Some(match (writer, reader) {
+            (Schema::Union(writers), _) => {
+                let mut variants = Vec::with_capacity(writers.len());
+                for (index, writer) in writers.iter().enumerate() {
+                    // variants of the writer that cannot be read only error when read
+                    let errors = self.errors.len();
+                    self.path.push(format!("[{}]", index));
+                    let resolved = self.resolve(writer, reader);
+                    self.path.pop();
+                    let incompatibilities = self.errors.drain(errors..).collect::<Vec<_>>();
+                    self.deferred.extend(incompatibilities.iter().cloned());
+                    variants.push(match (resolved, incompatibilities.into_iter().next()) {
+                        (Some(resolved), _) => resolved,
+                        (None, Some(incompatibility)) => Resolved::Incompatible(incompatibility),
+                        (None, None) => Resolved::Incompatible(Incompatibility {
+                            path: join_path(&self.path),
+                            reason: format!("the variant {} cannot be read", index),
+                        }),
+                    });
+                }
+                Resolved::Union(variants)
+            }
+            (_, Schema::Union(readers)) => return self.resolve_to_union(writer, readers),
+            (Schema::Null(_), Schema::Null(_)) => Resolved::Null,
+            (Schema::Boolean(_), Schema::Boolean(_)) => Resolved::Boolean,
+            (Schema::Int(_), Schema::Int(_)) => Resolved::Int,
+            (Schema::Int(_), Schema::Long(_)) => Resolved::Promote(IntToLong),
+            (Schema::Int(_), Schema::Float(_)) => Resolved::Promote(IntToFloat),
+            (Schema::Int(_), Schema::Double(_)) => Resolved::Promote(IntToDouble),
+            (Schema::Long(_), Schema::Long(_)) => Resolved::Long,
+            (Schema::Long(_), Schema::Float(_)) => Resolved::Promote(LongToFloat),
+            (Schema::Long(_), Schema::Double(_)) => Resolved::Promote(LongToDouble),
+            (Schema::Float(_), Schema::Float(_)) => Resolved::Float,
+            (Schema::Float(_), Schema::Double(_)) => Resolved::Promote(FloatToDouble),
+            (Schema::Double(_), Schema::Double(_)) => Resolved::Double,
+            (Schema::Bytes(_), Schema::Bytes(_)) => Resolved::Bytes,
+            (Schema::Bytes(_), Schema::String(_)) => Resolved::Promote(BytesToString),
+            (Schema::String(_), Schema::String(_)) => Resolved::String,
+            (Schema::String(_), Schema::Bytes(_)) => Resolved::Promote(StringToBytes),
+            (Schema::Array(writer), Schema::Array(reader)) => {
+                self.path.push("items".to_string());
+                let resolved = self.resolve(writer, reader);
+                self.path.pop();
+                Resolved::Array(Box::new(resolved?))
+            }
+            (Schema::Map(writer), Schema::Map(reader)) => {
+                self.path.push("values".to_string());
+                let resolved = self.resolve(writer, reader);
+                self.path.pop();
+                Resolved::Map(Box::new(resolved?))
+            }
+            (Schema::Record(w), Schema::Record(r)) if names_match(&w.name, &r.name, &r.aliases) => {
+                return self.resolve_named(writer, reader);
+            }
+            (Schema::Enum(w), Schema::Enum(r)) if names_match(&w.name, &r.name, &r.aliases) => {
+                return self.resolve_named(writer, reader);
+            }
+            (Schema::Fixed(w), Schema::Fixed(r)) if names_match(&w.name, &r.name, &r.aliases) => {
+                return self.resolve_named(writer, reader);
+            }
+            (Schema::Record(_), Schema::Record(_))
+            | (Schema::Enum(_), Schema::Enum(_))
+            | (Schema::Fixed(_), Schema::Fixed(_)) => {
+                self.error(format!(
+                    "name changed from {} to {}",
+                    describe(writer),
+                    describe(reader)
+                ));
+                return None;
+            }
+            _ => {
+                self.error(format!(
+                    "type changed from {} to {}",
+                    describe(writer),
+                    describe(reader)
+                ));
+                return None;
+            }
+        })
+    }
+
+    /// Resolves a non-union `writer` against the first variant of the reader's union that
+    /// matches it: a variant of the same type (or name), or else one it can be promoted to.
+    fn resolve_to_union(&mut self, writer: &'a Schema, readers: &'a [Schema]) -> Option<Resolved> {
+        let reader_names = &self.reader_names;
+        let same_kind = readers.iter().position(|reader| {
+            deref(reader, reader_names)
+                .map(|reader| same_kind(writer, reader))
+                .unwrap_or(false)
+        });
+        if let Some(index) = same_kind {
+            let resolved = self.resolve(writer, &readers[index])?;
+            return Some(Resolved::ToUnion(index, Box::new(resolved)));
+        }
+
+        for (index, reader) in readers.iter().enumerate() {
+            // try each variant, discarding the state of failed attempts
+            let (errors, deferred, named) =
+                (self.errors.len(), self.deferred.len(), self.named.len());
+            if let Some(resolved) = self.resolve(writer, reader) {
+                return Some(Resolved::ToUnion(index, Box::new(resolved)));
+            }
+            self.errors.truncate(errors);
+            self.deferred.truncate(deferred);
+            self.named.truncate(named);
+            self.indices.retain(|_, index| *index < named);
+        }
+        self.error(format!(
+            "no variant of the reader's union matches {}",
+            describe(writer)
+        ));
+        None
+    }
+
+    /// Resolves two named types, which may be recursive, into `self.named`
+    fn resolve_named(&mut self, writer: &'a Schema, reader: &'a Schema) -> Option<Resolved> {
+        let key = (writer as *const Schema, reader as *const Schema);
+        if let Some(index) = self.indices.get(&key) {
+            return Some(Resolved::Named(*index));
+        }
+        let index = self.named.len();
+        // a placeholder until resolved, so that recursive types refer to it
+        self.named.push(Resolved::Null);
+        self.indices.insert(key, index);
+
+        let resolved = match (writer, reader) {
+            (Schema::Record(writer), Schema::Record(reader)) => {
+                self.resolve_record(&writer.fields, &reader.fields)
+            }
+            (Schema::Enum(writer), Schema::Enum(reader)) => {
+                let symbols = writer
+                    .symbols
+                    .iter()
+                    .map(|symbol| {
+                        if reader.symbols.contains(symbol) {
+                            Ok(symbol.clone())
+                        } else {
+                            reader.default.clone().ok_or_else(|| Incompatibility {
+                                path: join_path(&self.path),
+                                reason: format!(
+                                    "the symbol \"{}\" is missing and the reader has no default",
+                                    symbol
+                                ),
+                            })
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                self.deferred.extend(
+                    symbols
+                        .iter()
+                        .filter_map(|symbol| symbol.as_ref().err())
+                        .cloned(),
+                );
+                Some(Resolved::Enum(symbols))
+            }
+            (Schema::Fixed(writer), Schema::Fixed(reader)) => {
+                if writer.size == reader.size {
+                    Some(Resolved::Fixed(writer.size))
+                } else {
+                    self.error(format!(
+                        "size changed from {} to {}",
+                        writer.size, reader.size
+                    ));
+                    None
+                }
+            }
+            _ => {
+                self.error(format!(
+                    "type changed from {} to {}",
+                    describe(writer),
+                    describe(reader)
+                ));
+                None
+            }
+        };
+
+        match resolved {
+            Some(resolved) => {
+                self.named[index] = resolved;
+                Some(Resolved::Named(index))
+            }
+            None => {
+                // discard the placeholder and every type that may refer to it
+                self.named.truncate(index);
+                self.indices.retain(|_, position| *position < index);
+                None
+            }
+        }
+    }
+
+    fn resolve_record(&mut self, writers: &'a [Field], readers: &'a [Field]) -> Option<Resolved> {
+        let mut is_valid = true;
+        let mut read = vec![false; readers.len()];
+        let mut fields = Vec::with_capacity(writers.len());
+        for writer in writers {
+            let position = readers.iter().position(|reader| {
+                reader.name == writer.name || reader.aliases.contains(&writer.name)
+            });
+            match position {
+                Some(position) => {
+                    read[position] = true;
+                    self.path.push(readers[position].name.clone());
+                    let resolved = self.resolve(&writer.schema, &readers[position].schema);
+                    self.path.pop();
+                    match resolved {
+                        Some(resolved) => fields.push(ResolvedField::Read(position, resolved)),
+                        None => is_valid = false,
+                    }
+                }
+                None => fields.push(ResolvedField::Skip(self.skip(&writer.schema))),
+            }
+        }
+
+        let mut defaults = vec![];
+        for (position, reader) in readers.iter().enumerate() {
+            if read[position] {
+                continue;
+            }
+            match default_value(reader, &self.reader_names) {
+                Ok(value) => defaults.push((position, value)),
+                Err(reason) => {
+                    self.path.push(reader.name.clone());
+                    self.error(reason);
+                    self.path.pop();
+                    is_valid = false;
+                }
+            }
+        }
+
+        if is_valid {
+            Some(Resolved::Record {
+                names: readers.iter().map(|field| field.name.clone()).collect(),
+                fields,
+                defaults,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the resolution of the writer's `schema` against itself, used to skip its data
+    fn skip(&mut self, schema: &'a Schema) -> Resolved {
+        let schema = match deref(schema, &self.writer_names) {
+            Ok(schema) => schema,
+            Err(name) => {
+                return Resolved::Incompatible(Incompatibility {
+                    path: join_path(&self.path),
+                    reason: format!("the type \"{}\" is not defined", name),
+                })
+            }
+        };
+        match schema {
+            Schema::Null(_) => Resolved::Null,
+            Schema::Boolean(_) => Resolved::Boolean,
+            Schema::Int(_) => Resolved::Int,
+            Schema::Long(_) => Resolved::Long,
+            Schema::Float(_) => Resolved::Float,
+            Schema::Double(_) => Resolved::Double,
+            Schema::Bytes(_) => Resolved::Bytes,
+            Schema::String(_) => Resolved::String,
+            Schema::Array(item) => Resolved::Array(Box::new(self.skip(item))),
+            Schema::Map(item) => Resolved::Map(Box::new(self.skip(item))),
+            Schema::Union(schemas) => {
+                Resolved::Union(schemas.iter().map(|schema| self.skip(schema)).collect())
+            }
+            Schema::Record(_) | Schema::Enum(_) | Schema::Fixed(_) => self.skip_named(schema),
+            Schema::Annotated(schema, _) => self.skip(schema),
+            Schema::Ref(name) => Resolved::Incompatible(Incompatibility {
+                path: join_path(&self.path),
+                reason: format!("the type \"{}\" is not resolved", name),
+            }),
+        }
+    }
+
+    /// Returns the resolution of the writer's named `schema`, which may be recursive, against itself
+    fn skip_named(&mut self, schema: &'a Schema) -> Resolved {
+        let key = (schema as *const Schema, schema as *const Schema);
+        if let Some(index) = self.indices.get(&key) {
+            return Resolved::Named(*index);
+        }
+        let index = self.named.len();
+        self.named.push(Resolved::Null);
+        self.indices.insert(key, index);
+        self.named[index] = match schema {
+            Schema::Record(record) => Resolved::Record {
+                names: vec![],
+                fields: record
+                    .fields
+                    .iter()
+                    .map(|field| ResolvedField::Skip(self.skip(&field.schema)))
+                    .collect(),
+                defaults: vec![],
+            },
+            Schema::Enum(enum_) => Resolved::Enum(enum_.symbols.iter().cloned().map(Ok).collect()),
+            Schema::Fixed(fixed) => Resolved::Fixed(fixed.size),
+            schema => Resolved::Incompatible(Incompatibility {
+                path: join_path(&self.path),
+                reason: format!("{} is not a named type", describe(schema)),
+            }),
+        };
+        Resolved::Named(index)
+    }
+}
+
+/// Returns the schema that `schema` refers to, or the name of the undefined type it refers to.
+/// The returned schema is neither a [`Schema::Ref`] nor a [`Schema::Annotated`].
+fn deref<'a>(
+    schema: &'a Schema,
+    names: &HashMap<String, &'a Schema>,
+) -> Result<&'a Schema, String> {
+    match schema {
+        Schema::Ref(name) => names.get(name).copied().ok_or_else(|| name.clone()),
+        // custom attributes do not change how data is read
+        Schema::Annotated(schema, _) => deref(schema, names),
+        schema => Ok(schema),
+    }
+}
+
+/// Whether the writer's named type `writer` is read by the reader's named type `reader`
+/// with `aliases`, compared by their unqualified names.
+fn names_match(writer: &str, reader: &str, aliases: &[String]) -> bool {
+    let writer = unqualified(writer);
+    unqualified(reader) == writer || aliases.iter().any(|alias| unqualified(alias) == writer)
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Whether both schemas are of the same type and, for named types, have the same name
+fn same_kind(writer: &Schema, reader: &Schema) -> bool {
+    match (writer, reader) {
+        (Schema::Record(w), Schema::Record(r)) => names_match(&w.name, &r.name, &r.aliases),
+        (Schema::Enum(w), Schema::Enum(r)) => names_match(&w.name, &r.name, &r.aliases),
+        (Schema::Fixed(w), Schema::Fixed(r)) => names_match(&w.name, &r.name, &r.aliases),
+        (Schema::Int(_), Schema::Int(_))
+        | (Schema::Long(_), Schema::Long(_))
+        | (Schema::Bytes(_), Schema::Bytes(_))
+        | (Schema::String(_), Schema::String(_)) => true,
+        _ => std::mem::discriminant(writer) == std::mem::discriminant(reader),
+    }
+}
+
+/// Describes `schema` in error messages, e.g. `record "A"` or `long`
+fn describe(schema: &Schema) -> String {
+    match schema {
+        Schema::Record(_) => format!("record \"{}\"", schema.type_name()),
+        Schema::Enum(_) => format!("enum \"{}\"", schema.type_name()),
+        Schema::Fixed(_) => format!("fixed \"{}\"", schema.type_name()),
+        Schema::Ref(name) => format!("\"{}\"", name),
+        Schema::Annotated(schema, _) => describe(schema),
+        schema => schema.type_name().to_string(),
+    }
+}
+
+/// Returns the value of a reader's field that is missing from the writer's record
+fn default_value(field: &Field, names: &HashMap<String, &Schema>) -> Result<Value, String> {
+    let default = field.default.as_ref().ok_or_else(|| {
+        "the field is missing from the writer's record and has no default".to_string()
+    })?;
+    from_default(default, &field.schema, names)
+        .map_err(|reason| format!("the default is invalid: {}", reason))
+}
+
+impl Resolution {
+    /// Sets the maximum number of items of an array whose items are written in zero bytes,
+    /// as [`Decoder::with_max_empty_items`](crate::read::Decoder::with_max_empty_items) does.
+    pub fn with_max_empty_items(mut self, max_empty_items: u64) -> Self {
+        self.max_empty_items = max_empty_items;
+        self
+    }
+
+    /// Decodes a single datum of the writer's schema from `reader` as a value of the reader's schema.
+    /// # Error
+    /// This function errors iff the data is not described by the writer's schema, data that the
+    /// reader cannot read is found, records, arrays and maps are nested more than 128 times, or
+    /// an array has more items written in zero bytes than allowed by
+    /// [`Self::with_max_empty_items`].
+    pub fn decode<R: Read>(&self, reader: &mut R) -> Result<Value, Error> {
+        self.decode_resolved(reader, &self.root, 0)
+    }
+
+    /// Decodes all rows of `block`, written with the writer's schema, into `values` of the reader's schema.
+    /// # Error
+    /// This function errors iff the block's data is not a sequence of `block.number_of_rows`
+    /// data that [`Self::decode`] reads.
+    pub fn decode_block(&self, block: &Block, values: &mut Vec<Value>) -> Result<(), Error> {
+        let mut data: &[u8] = block.data.as_ref();
+        values.reserve(block.number_of_rows);
+        for _ in 0..block.number_of_rows {
+            values.push(self.decode(&mut data)?);
+        }
+        if !data.is_empty() {
+            return Err(Error::OutOfSpec(format!(
+                "the block has {} bytes after its last row",
+                data.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decodes a datum of `resolved`, nested in `depth` records, arrays or maps
+    // each arm is a separate function so that this frame, which recursive types repeat, is small
+    fn decode_resolved<R: Read>(
+        &self,
+        reader: &mut R,
+        resolved: &Resolved,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        match resolved {
+            Resolved::Record {
+                names,
+                fields,
+                defaults,
+            } => self.decode_record(reader, names, fields, defaults, nest(depth)?),
+            Resolved::Array(item) => self.decode_array(reader, item, nest(depth)?),
+            Resolved::Map(item) => self.decode_map(reader, item, nest(depth)?),
+            Resolved::Union(variants) => self.decode_union(reader, variants, depth),
+            Resolved::ToUnion(index, resolved) => {
+                let value = self.decode_resolved(reader, resolved, depth)?;
+                Ok(Value::Union(*index, Box::new(value)))
+            }
+            Resolved::Named(index) => self.decode_resolved(reader, &self.named[*index], depth),
+            _ => decode_leaf(reader, resolved),
+        }
+    }
+
+    fn decode_record<R: Read>(
+        &self,
+        reader: &mut R,
+        names: &[String],
+        fields: &[ResolvedField],
+        defaults: &[(usize, Value)],
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = vec![None; names.len()];
+        for field in fields {
+            match field {
+                ResolvedField::Read(position, resolved) => {
+                    values[*position] = Some(self.decode_resolved(reader, resolved, depth)?)
+                }
+                ResolvedField::Skip(resolved) => {
+                    self.decode_resolved(reader, resolved, depth)?;
+                }
+            }
+        }
+        for (position, value) in defaults {
+            values[*position] = Some(value.clone());
+        }
+        Ok(Value::Record(
+            names
+                .iter()
+                .cloned()
+                .zip(values)
+                // every field of the reader is either read or has a default
+                .map(|(name, value)| (name, value.unwrap_or(Value::Null)))
+                .collect(),
+        ))
+    }
+
+    fn decode_array<R: Read>(
+        &self,
+        reader: &mut R,
+        item: &Resolved,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = vec![];
+        let max_items = self
+            .is_empty(item, &mut vec![])
+            .then_some(self.max_empty_items);
+        read_blocks(reader, max_items, |reader| {
+            values.push(self.decode_resolved(reader, item, depth)?);
+            Ok(())
+        })?;
+        Ok(Value::Array(values))
+    }
+
+    fn decode_map<R: Read>(
+        &self,
+        reader: &mut R,
+        item: &Resolved,
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let mut values = HashMap::new();
+        read_blocks(reader, None, |reader| {
+            let key = read_string(reader)?;
+            values.insert(key, self.decode_resolved(reader, item, depth)?);
+            Ok(())
+        })?;
+        Ok(Value::Map(values))
+    }
+
+    fn decode_union<R: Read>(
+        &self,
+        reader: &mut R,
+        variants: &[Resolved],
+        depth: usize,
+    ) -> Result<Value, Error> {
+        let index = read_len(reader)?;
+        let resolved = variants.get(index).ok_or_else(|| {
+            Error::OutOfSpec(format!("the union has no variant with index {}", index))
+        })?;
+        self.decode_resolved(reader, resolved, depth)
+    }
+
+    /// Whether data of `resolved` is written in zero bytes, where `visiting` are the named types
+    /// being checked
+    fn is_empty(&self, resolved: &Resolved, visiting: &mut Vec<usize>) -> bool {
+        match resolved {
+            Resolved::Null => true,
+            Resolved::Record { fields, .. } => fields.iter().all(|field| match field {
+                ResolvedField::Read(_, resolved) | ResolvedField::Skip(resolved) => {
+                    self.is_empty(resolved, visiting)
+                }
+            }),
+            Resolved::ToUnion(_, resolved) => self.is_empty(resolved, visiting),
+            Resolved::Fixed(size) => *size == 0,
+            Resolved::Named(index) => {
+                // a type containing itself is never fully read
+                if visiting.contains(index) {
+                    return false;
+                }
+                visiting.push(*index);
+                let is_empty = self.is_empty(&self.named[*index], visiting);
+                visiting.pop();
+                is_empty
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Decodes a datum of `resolved`, which contains no other data
+fn decode_leaf<R: Read>(reader: &mut R, resolved: &Resolved) -> Result<Value, Error> {
+    Ok(match resolved {
+        Resolved::Null => Value::Null,
+        Resolved::Boolean => Value::Boolean(read_boolean(reader)?),
+        Resolved::Int => Value::Int(read_int(reader)?),
+        Resolved::Long => Value::Long(zigzag_i64(reader)?),
+        Resolved::Float => Value::Float(read_float(reader)?),
+        Resolved::Double => Value::Double(read_double(reader)?),
+        Resolved::Bytes => Value::Bytes(read_bytes(reader)?),
+        Resolved::String => Value::String(read_string(reader)?),
+        Resolved::Promote(promotion) => match promotion {
+            Promotion::IntToLong => Value::Long(read_int(reader)? as i64),
+            Promotion::IntToFloat => Value::Float(read_int(reader)? as f32),
+            Promotion::IntToDouble => Value::Double(read_int(reader)? as f64),
+            Promotion::LongToFloat => Value::Float(zigzag_i64(reader)? as f32),
+            Promotion::LongToDouble => Value::Double(zigzag_i64(reader)? as f64),
+            Promotion::FloatToDouble => Value::Double(read_float(reader)? as f64),
+            Promotion::StringToBytes => Value::Bytes(read_bytes(reader)?),
+            Promotion::BytesToString => Value::String(read_string(reader)?),
+        },
+        Resolved::Enum(symbols) => {
+            let index = read_len(reader)?;
+            let symbol = symbols.get(index).ok_or_else(|| {
+                Error::OutOfSpec(format!("the enum has no symbol with index {}", index))
+            })?;
+            match symbol {
+                Ok(symbol) => Value::Enum(symbol.clone()),
+                Err(incompatibility) => {
+                    return Err(Error::IncompatibleSchemas(vec![incompatibility.clone()]))
+                }
+            }
+        }
+        Resolved::Fixed(size) => Value::Fixed(read_exact(reader, *size)?),
+        Resolved::Incompatible(incompatibility) => {
+            return Err(Error::IncompatibleSchemas(vec![incompatibility.clone()]))
+        }
+        _ => unreachable!("only called with data without children"),
+    })
+}
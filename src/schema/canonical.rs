@@ -0,0 +1,124 @@
+use super::*;
+
+impl Schema {
+    /// Returns the [Parsing Canonical Form](https://avro.apache.org/docs/current/spec.html#Parsing+Canonical+Form+for+Schemas)
+    /// of this schema: its JSON without whitespace, with fullnames, with only the attributes relevant
+    /// to parsing data (e.g. no `doc`, `aliases`, `default` nor `logicalType`) in a fixed order.
+    ///
+    /// Two schemas with the same canonical form describe the same data.
+    pub fn canonical_form(&self) -> String {
+        let mut result = String::new();
+        write_canonical(self, None, &mut result);
+        result
+    }
+}
+
+fn write_str(value: &str, result: &mut String) {
+    // serializing a `&str` is infallible
+    result.push_str(&serde_json::to_string(value).unwrap());
+}
+
+/// Writes `"name":"<fullname>","type":"<type_>"` and returns the namespace of the named type
+fn write_name(
+    name: &str,
+    namespace: Option<&str>,
+    enclosing: Option<&str>,
+    type_: &str,
+    result: &mut String,
+) -> Option<String> {
+    let name = fullname(name, namespace.or(enclosing));
+    result.push_str("{\"name\":");
+    write_str(&name, result);
+    result.push_str(",\"type\":\"");
+    result.push_str(type_);
+    result.push('"');
+    name.rsplit_once('.')
+        .map(|(namespace, _)| namespace.to_string())
+}
+
+fn write_canonical(schema: &Schema, namespace: Option<&str>, result: &mut String) {
+    match schema {
+        Schema::Null(_) => result.push_str("\"null\""),
+        Schema::Boolean(_) => result.push_str("\"boolean\""),
+        Schema::Int(_) => result.push_str("\"int\""),
+        Schema::Long(_) => result.push_str("\"long\""),
+        Schema::Float(_) => result.push_str("\"float\""),
+        Schema::Double(_) => result.push_str("\"double\""),
+        Schema::Bytes(_) => result.push_str("\"bytes\""),
+        Schema::String(_) => result.push_str("\"string\""),
+        Schema::Record(record) => {
+            let namespace = write_name(
+                &record.name,
+                record.namespace.as_deref(),
+                namespace,
+                "record",
+                result,
+            );
+            result.push_str(",\"fields\":[");
+            for (i, field) in record.fields.iter().enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                result.push_str("{\"name\":");
+                write_str(&field.name, result);
+                result.push_str(",\"type\":");
+                write_canonical(&field.schema, namespace.as_deref(), result);
+                result.push('}');
+            }
+            result.push_str("]}");
+        }
+        Schema::Enum(enum_) => {
+            write_name(
+                &enum_.name,
+                enum_.namespace.as_deref(),
+                namespace,
+                "enum",
+                result,
+            );
+            result.push_str(",\"symbols\":[");
+            for (i, symbol) in enum_.symbols.iter().enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                write_str(symbol, result);
+            }
+            result.push_str("]}");
+        }
+        Schema::Array(item) => {
+            result.push_str("{\"type\":\"array\",\"items\":");
+            write_canonical(item, namespace, result);
+            result.push('}');
+        }
+        Schema::Map(item) => {
+            result.push_str("{\"type\":\"map\",\"values\":");
+            write_canonical(item, namespace, result);
+            result.push('}');
+        }
+        Schema::Union(schemas) => {
+            result.push('[');
+            for (i, schema) in schemas.iter().enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                write_canonical(schema, namespace, result);
+            }
+            result.push(']');
+        }
+        Schema::Fixed(fixed) => {
+            write_name(
+                &fixed.name,
+                fixed.namespace.as_deref(),
+                namespace,
+                "fixed",
+                result,
+            );
+            result.push_str(",\"size\":");
+            result.push_str(&fixed.size.to_string());
+            result.push('}');
+        }
+        // references are already fullnames
+        Schema::Ref(name) => write_str(name, result),
+        // custom attributes are not part of the canonical form
+        Schema::Annotated(schema, _) => write_canonical(schema, namespace, result),
+    }
+}
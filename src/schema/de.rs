@@ -1,4 +1,7 @@
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 use serde::{
     de::{MapAccess, SeqAccess, Visitor},
@@ -11,18 +14,71 @@ use super::*;
 fn to_primitive(v: &str) -> Option<Schema> {
     use Schema::*;
     Some(match v {
-        "null" => Null,
-        "boolean" => Boolean,
+        "null" => Null(None),
+        "boolean" => Boolean(None),
         "bytes" => Bytes(None),
         "string" => String(None),
         "int" => Int(None),
         "long" => Long(None),
-        "float" => Float,
-        "double" => Double,
+        "float" => Float(None),
+        "double" => Double(None),
         _ => return None,
     })
 }
 
+/// State shared while parsing a [`Schema`]: the namespace enclosing the type being parsed
+/// and the fullnames of all named types defined so far.
+#[derive(Debug, Default)]
+struct Context {
+    namespace: Option<String>,
+    names: HashSet<String>,
+}
+
+impl Context {
+    /// Registers the named type `name` and returns the namespace its children are declared in.
+    fn register<E: serde::de::Error>(
+        &mut self,
+        name: &str,
+        namespace: Option<&str>,
+    ) -> Result<Option<String>, E> {
+        let fullname = fullname(name, namespace.or(self.namespace.as_deref()));
+        if to_primitive(&fullname).is_some() {
+            return Err(E::custom(format!(
+                "\"{}\" is a primitive type and cannot be redefined",
+                fullname
+            )));
+        }
+        let enclosing = fullname.rsplit_once('.').map(|(ns, _)| ns.to_string());
+        if !self.names.insert(fullname.clone()) {
+            return Err(E::custom(format!(
+                "\"{}\" is defined more than once",
+                fullname
+            )));
+        }
+        Ok(enclosing)
+    }
+
+    /// Resolves `name` against the types defined so far, returning its fullname.
+    fn resolve(&self, name: &str) -> Option<String> {
+        let fullname = fullname(name, self.namespace.as_deref());
+        if self.names.contains(&fullname) {
+            Some(fullname)
+        } else if self.names.contains(name) {
+            // names in the null namespace are visible from every namespace
+            Some(name.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+fn to_named<E: serde::de::Error>(name: &str, context: &Context) -> Result<Schema, E> {
+    context
+        .resolve(name)
+        .map(Schema::Ref)
+        .ok_or_else(|| E::custom(format!("\"{}\" is not a defined type", name)))
+}
+
 fn get_type<E: serde::de::Error>(map: &mut HashMap<String, Value>) -> Result<String, E> {
     if let Some(v) = map.remove("type") {
         if let Value::String(v) = v {
@@ -67,6 +123,22 @@ fn remove_usize<E: serde::de::Error>(
         .transpose()
 }
 
+/// Removes the `precision` and `scale` of a decimal, or leaves them untouched when they are not
+/// valid (a positive precision and a non-negative scale)
+fn remove_decimal(data: &mut HashMap<String, Value>) -> Option<(usize, usize)> {
+    let precision = data
+        .get("precision")
+        .and_then(|x| x.as_u64())
+        .filter(|x| *x > 0)?;
+    let scale = match data.get("scale") {
+        Some(scale) => scale.as_u64()?,
+        None => 0,
+    };
+    data.remove("precision");
+    data.remove("scale");
+    Some((precision as usize, scale as usize))
+}
+
 fn remove_vec_string<E: serde::de::Error>(
     data: &mut HashMap<String, Value>,
     key: &str,
@@ -86,54 +158,80 @@ fn remove_vec_string<E: serde::de::Error>(
     }
 }
 
-fn to_enum<E: serde::de::Error>(data: &mut HashMap<String, Value>) -> Result<Schema, E> {
+/// Removes all remaining entries of `data`: the custom attributes of a type
+fn remove_properties(data: &mut HashMap<String, Value>) -> BTreeMap<String, Value> {
+    data.drain().collect()
+}
+
+fn to_enum<E: serde::de::Error>(
+    data: &mut HashMap<String, Value>,
+    context: &mut Context,
+) -> Result<Schema, E> {
+    let name = remove_string(data, "name")?
+        .ok_or_else(|| serde::de::Error::custom("name is required in enum"))?;
+    let namespace = remove_string(data, "namespace")?;
+    context.register(&name, namespace.as_deref())?;
+
     Ok(Schema::Enum(Enum {
-        name: remove_string(data, "name")?
-            .ok_or_else(|| serde::de::Error::custom("name is required in enum"))?,
-        namespace: remove_string(data, "namespace")?,
+        name,
+        namespace,
         aliases: remove_vec_string(data, "aliases")?,
         doc: remove_string(data, "doc")?,
         symbols: remove_vec_string(data, "symbols")?,
         default: remove_string(data, "default")?,
+        properties: remove_properties(data),
     }))
 }
 
-fn to_map<E: serde::de::Error>(data: &mut HashMap<String, Value>) -> Result<Schema, E> {
-    let item = data
-        .remove("values")
+fn to_map<E: serde::de::Error>(
+    data: &mut HashMap<String, Value>,
+    context: &mut Context,
+) -> Result<Schema, E> {
+    let schema = to_schema(data, "values", context)?
         .ok_or_else(|| serde::de::Error::custom("values is required in a map"))?;
-    let schema: Schema = serde_json::from_value(item).map_err(serde::de::Error::custom)?;
     Ok(Schema::Map(Box::new(schema)))
 }
 
+fn parse_schema<E: serde::de::Error>(value: Value, context: &mut Context) -> Result<Schema, E> {
+    value
+        .deserialize_option(SchemaVisitor { context })
+        .map_err(serde::de::Error::custom)
+}
+
 fn to_schema<E: serde::de::Error>(
     data: &mut HashMap<String, Value>,
     key: &str,
+    context: &mut Context,
 ) -> Result<Option<Schema>, E> {
     let schema = data.remove(key);
     schema
-        .map(|schema| serde_json::from_value(schema).map_err(serde::de::Error::custom))
+        .map(|schema| parse_schema(schema, context))
         .transpose()
 }
 
-fn to_array<E: serde::de::Error>(data: &mut HashMap<String, Value>) -> Result<Schema, E> {
-    let schema =
-        to_schema(data, "items")?.ok_or_else(|| E::custom("items is required in an array"))?;
+fn to_array<E: serde::de::Error>(
+    data: &mut HashMap<String, Value>,
+    context: &mut Context,
+) -> Result<Schema, E> {
+    let schema = to_schema(data, "items", context)?
+        .ok_or_else(|| E::custom("items is required in an array"))?;
     Ok(Schema::Array(Box::new(schema)))
 }
 
-fn to_field<E: serde::de::Error>(data: Value) -> Result<Field, E> {
-    serde_json::from_value(data).map_err(E::custom)
+fn to_field<E: serde::de::Error>(data: Value, context: &mut Context) -> Result<Field, E> {
+    data.deserialize_map(FieldVisitor { context })
+        .map_err(E::custom)
 }
 
 fn to_vec_fields<E: serde::de::Error>(
     data: &mut HashMap<String, Value>,
     key: &str,
+    context: &mut Context,
 ) -> Result<Vec<Field>, E> {
     match data.remove(key) {
         Some(s) => {
             if let Value::Array(x) = s {
-                x.into_iter().map(to_field).collect()
+                x.into_iter().map(|x| to_field(x, context)).collect()
             } else {
                 Err(E::custom(format!("{} must be a string", key)))
             }
@@ -142,40 +240,59 @@ fn to_vec_fields<E: serde::de::Error>(
     }
 }
 
-fn to_record<E: serde::de::Error>(data: &mut HashMap<String, Value>) -> Result<Schema, E> {
+fn to_record<E: serde::de::Error>(
+    data: &mut HashMap<String, Value>,
+    context: &mut Context,
+) -> Result<Schema, E> {
+    let name = remove_string(data, "name")?
+        .ok_or_else(|| serde::de::Error::custom("name is required in record"))?;
+    let namespace = remove_string(data, "namespace")?;
+
+    // the record is registered before its fields so that they can refer to it (recursive types)
+    let enclosing = context.register(&name, namespace.as_deref())?;
+    let outer = std::mem::replace(&mut context.namespace, enclosing);
+    let fields = to_vec_fields(data, "fields", context);
+    context.namespace = outer;
+
     Ok(Schema::Record(Record {
-        name: remove_string(data, "name")?
-            .ok_or_else(|| serde::de::Error::custom("name is required in enum"))?,
-        namespace: remove_string(data, "namespace")?,
+        name,
+        namespace,
         aliases: remove_vec_string(data, "aliases")?,
         doc: remove_string(data, "doc")?,
-        fields: to_vec_fields(data, "fields")?,
+        fields: fields?,
+        properties: remove_properties(data),
     }))
 }
 
-fn to_fixed<E: serde::de::Error>(data: &mut HashMap<String, Value>) -> Result<Schema, E> {
+fn to_fixed<E: serde::de::Error>(
+    data: &mut HashMap<String, Value>,
+    context: &mut Context,
+) -> Result<Schema, E> {
     let size = remove_usize(data, "size")?
         .ok_or_else(|| serde::de::Error::custom("size is required in fixed"))?;
 
-    let logical = remove_string(data, "logicalType")?.unwrap_or_default();
-    let logical = match logical.as_ref() {
-        "decimal" => {
-            let precision = remove_usize(data, "precision")?;
-            let scale = remove_usize(data, "scale")?.unwrap_or_default();
-            precision.map(|p| FixedLogical::Decimal(p, scale))
-        }
-        "duration" => Some(FixedLogical::Duration),
-        _ => None,
-    };
+    let logical = remove_string(data, "logicalType")?.map(|logical| match logical.as_ref() {
+        "decimal" => match remove_decimal(data) {
+            Some((precision, scale)) => FixedLogical::Decimal(precision, scale),
+            None => FixedLogical::Other(logical),
+        },
+        "duration" => FixedLogical::Duration,
+        _ => FixedLogical::Other(logical),
+    });
+
+    let name = remove_string(data, "name")?
+        .ok_or_else(|| serde::de::Error::custom("name is required in fixed"))?;
+    let namespace = remove_string(data, "namespace")?;
+    context.register(&name, namespace.as_deref())?;
 
     Ok(Schema::Fixed(Fixed {
-        name: remove_string(data, "name")?
-            .ok_or_else(|| serde::de::Error::custom("name is required in fixed"))?,
-        namespace: remove_string(data, "namespace")?,
+        name,
+        namespace,
         aliases: remove_vec_string(data, "aliases")?,
         doc: remove_string(data, "doc")?,
         size,
         logical,
+        properties: remove_properties(data),
     }))
 }
 
@@ -199,9 +316,11 @@ fn to_order<E: serde::de::Error>(
         .transpose()
 }
 
-struct SchemaVisitor {}
+struct SchemaVisitor<'a> {
+    context: &'a mut Context,
+}
 
-impl<'de> Visitor<'de> for SchemaVisitor {
+impl<'a, 'de> Visitor<'de> for SchemaVisitor<'a> {
     type Value = Schema;
 
     // Format a message stating what data this Visitor expects to receive.
@@ -213,22 +332,24 @@ impl<'de> Visitor<'de> for SchemaVisitor {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(SchemaVisitor {})
+        deserializer.deserialize_any(self)
     }
 
     fn visit_none<E>(self) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
-        Ok(Schema::Null)
+        Ok(Schema::Null(None))
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: serde::de::Error,
     {
-        to_primitive(v)
-            .ok_or_else(|| serde::de::Error::custom("string must be a valid primitive Schema"))
+        match to_primitive(v) {
+            Some(schema) => Ok(schema),
+            None => to_named(v, self.context),
+        }
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -237,8 +358,7 @@ impl<'de> Visitor<'de> for SchemaVisitor {
     {
         let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0));
         while let Some(item) = seq.next_element::<Value>()? {
-            let schema: Schema = serde_json::from_value(item).map_err(serde::de::Error::custom)?;
-            vec.push(schema)
+            vec.push(parse_schema(item, self.context)?)
         }
         Ok(Schema::Union(vec))
     }
@@ -257,60 +377,80 @@ impl<'de> Visitor<'de> for SchemaVisitor {
 
         let (schema, type_) = get_type(&mut map).map(|x| (to_primitive(&x), x))?;
 
-        if let Some(schema) = schema {
-            Ok(match type_.as_ref() {
-                "string" => {
-                    let logical = remove_string(&mut map, "logicalType")?.unwrap_or_default();
-                    match logical.as_ref() {
-                        "uuid" => Schema::String(Some(StringLogical::Uuid)),
-                        _ => schema,
-                    }
-                }
-                "int" => {
-                    let logical = remove_string(&mut map, "logicalType")?.unwrap_or_default();
-                    match logical.as_ref() {
-                        "date" => Schema::Int(Some(IntLogical::Date)),
-                        "time-millis" => Schema::Int(Some(IntLogical::Time)),
-                        _ => schema,
-                    }
-                }
-                "long" => {
-                    let logical = remove_string(&mut map, "logicalType")?.unwrap_or_default();
-                    match logical.as_ref() {
-                        "time-micros" => Schema::Long(Some(LongLogical::Time)),
-                        "timestamp-millis" => Schema::Long(Some(LongLogical::TimestampMillis)),
-                        "timestamp-micros" => Schema::Long(Some(LongLogical::TimestampMicros)),
-                        "local-timestamp-millis" => {
-                            Schema::Long(Some(LongLogical::LocalTimestampMillis))
-                        }
-                        "local-timestamp-micros" => {
-                            Schema::Long(Some(LongLogical::LocalTimestampMicros))
-                        }
-                        _ => schema,
-                    }
-                }
-                "bytes" => {
-                    let logical = remove_string(&mut map, "logicalType")?.unwrap_or_default();
-                    match logical.as_ref() {
-                        "decimal" => {
-                            let precision = remove_usize(&mut map, "precision")?;
-                            let scale = remove_usize(&mut map, "scale")?.unwrap_or_default();
-                            Schema::Bytes(precision.map(|p| BytesLogical::Decimal(p, scale)))
-                        }
-                        _ => schema,
-                    }
-                }
+        let schema = if let Some(schema) = schema {
+            match type_.as_ref() {
+                "string" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::String(Some(match logical.as_ref() {
+                        "uuid" => StringLogical::Uuid,
+                        _ => StringLogical::Other(logical),
+                    })),
+                    None => schema,
+                },
+                "int" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Int(Some(match logical.as_ref() {
+                        "date" => IntLogical::Date,
+                        "time-millis" => IntLogical::Time,
+                        _ => IntLogical::Other(logical),
+                    })),
+                    None => schema,
+                },
+                "long" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Long(Some(match logical.as_ref() {
+                        "time-micros" => LongLogical::Time,
+                        "timestamp-millis" => LongLogical::TimestampMillis,
+                        "timestamp-micros" => LongLogical::TimestampMicros,
+                        "local-timestamp-millis" => LongLogical::LocalTimestampMillis,
+                        "local-timestamp-micros" => LongLogical::LocalTimestampMicros,
+                        _ => LongLogical::Other(logical),
+                    })),
+                    None => schema,
+                },
+                "bytes" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Bytes(Some(match logical.as_ref() {
+                        "decimal" => match remove_decimal(&mut map) {
+                            Some((precision, scale)) => BytesLogical::Decimal(precision, scale),
+                            None => BytesLogical::Other(logical),
+                        },
+                        _ => BytesLogical::Other(logical),
+                    })),
+                    None => schema,
+                },
+                "null" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Null(Some(NullLogical::Other(logical))),
+                    None => schema,
+                },
+                "boolean" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Boolean(Some(BooleanLogical::Other(logical))),
+                    None => schema,
+                },
+                "float" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Float(Some(FloatLogical::Other(logical))),
+                    None => schema,
+                },
+                "double" => match remove_string(&mut map, "logicalType")? {
+                    Some(logical) => Schema::Double(Some(DoubleLogical::Other(logical))),
+                    None => schema,
+                },
                 _ => schema,
-            })
+            }
         } else {
             match type_.as_ref() {
-                "enum" => to_enum(&mut map),
-                "map" => to_map(&mut map),
-                "array" => to_array(&mut map),
-                "record" => to_record(&mut map),
-                "fixed" => to_fixed(&mut map),
-                other => todo!("{}", other),
+                "enum" => to_enum(&mut map, self.context)?,
+                "map" => to_map(&mut map, self.context)?,
+                "array" => to_array(&mut map, self.context)?,
+                "record" => to_record(&mut map, self.context)?,
+                "fixed" => to_fixed(&mut map, self.context)?,
+                other => to_named(other, self.context)?,
             }
+        };
+        // named types keep their custom attributes; other types are annotated with them
+        if map.is_empty() {
+            Ok(schema)
+        } else {
+            Ok(Schema::Annotated(
+                Box::new(schema),
+                remove_properties(&mut map),
+            ))
         }
     }
 }
@@ -320,13 +460,44 @@ impl<'de> Deserialize<'de> for Schema {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_option(SchemaVisitor {})
+        let mut context = Context::default();
+        let schema = deserializer.deserialize_option(SchemaVisitor {
+            context: &mut context,
+        })?;
+        // defaults are checked once all named types they may refer to are parsed
+        let names = schema.named_types();
+        for schema in names.values() {
+            if let Schema::Record(record) = schema {
+                for field in &record.fields {
+                    check_default(field, &names)?;
+                }
+            }
+        }
+        Ok(schema)
+    }
+}
+
+/// Checks that the default of `field`, if any, is a valid value of its schema
+fn check_default<E: serde::de::Error>(
+    field: &Field,
+    names: &HashMap<String, &Schema>,
+) -> Result<(), E> {
+    if let Some(default) = &field.default {
+        crate::value::from_default(default, &field.schema, names).map_err(|reason| {
+            E::custom(format!(
+                "the default of the field \"{}\" is invalid: {}",
+                field.name, reason
+            ))
+        })?;
     }
+    Ok(())
 }
 
-struct FieldVisitor {}
+struct FieldVisitor<'a> {
+    context: &'a mut Context,
+}
 
-impl<'de> Visitor<'de> for FieldVisitor {
+impl<'a, 'de> Visitor<'de> for FieldVisitor<'a> {
     type Value = Field;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
@@ -347,13 +518,14 @@ impl<'de> Visitor<'de> for FieldVisitor {
 
         Ok(Field {
             name: remove_string(&mut map, "name")?
-                .ok_or_else(|| serde::de::Error::custom("name is required in enum"))?,
+                .ok_or_else(|| serde::de::Error::custom("name is required in Field"))?,
             doc: remove_string(&mut map, "doc")?,
-            schema: to_schema(&mut map, "type")?
+            schema: to_schema(&mut map, "type", self.context)?
                 .ok_or_else(|| serde::de::Error::custom("type is required in Field"))?,
-            default: to_schema(&mut map, "default")?,
+            default: map.remove("default"),
             order: to_order(&mut map, "order")?,
             aliases: remove_vec_string(&mut map, "aliases")?,
+            properties: remove_properties(&mut map),
         })
     }
 }
@@ -363,6 +535,11 @@ impl<'de> Deserialize<'de> for Field {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(FieldVisitor {})
+        let mut context = Context::default();
+        let field = deserializer.deserialize_map(FieldVisitor {
+            context: &mut context,
+        })?;
+        check_default(&field, &field.schema.named_types())?;
+        Ok(field)
     }
 }
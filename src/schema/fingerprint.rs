@@ -0,0 +1,56 @@
+use super::Schema;
+
+/// The fingerprint of the empty string, as defined by the spec
+const EMPTY: u64 = 0xc15d_213a_a4d7_a795;
+
+const fn rabin_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut fp = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            fp = (fp >> 1) ^ (EMPTY & (fp & 1).wrapping_neg());
+            j += 1;
+        }
+        table[i] = fp;
+        i += 1;
+    }
+    table
+}
+
+static RABIN_TABLE: [u64; 256] = rabin_table();
+
+/// Returns the 64-bit Rabin fingerprint (CRC-64-AVRO) of `data`.
+fn rabin(data: &[u8]) -> u64 {
+    data.iter().fold(EMPTY, |fp, byte| {
+        (fp >> 8) ^ RABIN_TABLE[((fp ^ *byte as u64) & 0xff) as usize]
+    })
+}
+
+impl Schema {
+    /// Returns the 64-bit Rabin fingerprint (CRC-64-AVRO) of the
+    /// [canonical form](Schema::canonical_form) of this schema.
+    ///
+    /// The fingerprint's little-endian bytes (`to_le_bytes`) are the ones used by the spec's
+    /// single-object encoding and returned by the reference implementation.
+    pub fn fingerprint_rabin(&self) -> u64 {
+        rabin(self.canonical_form().as_bytes())
+    }
+
+    /// Returns the MD5 fingerprint of the [canonical form](Schema::canonical_form) of this schema.
+    #[cfg(feature = "fingerprint")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fingerprint")))]
+    pub fn fingerprint_md5(&self) -> [u8; 16] {
+        use md5::Digest;
+        md5::Md5::digest(self.canonical_form().as_bytes()).into()
+    }
+
+    /// Returns the SHA-256 fingerprint of the [canonical form](Schema::canonical_form) of this schema.
+    #[cfg(feature = "fingerprint")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fingerprint")))]
+    pub fn fingerprint_sha256(&self) -> [u8; 32] {
+        use sha2::Digest;
+        sha2::Sha256::digest(self.canonical_form().as_bytes()).into()
+    }
+}
@@ -1,23 +1,30 @@
 //! Contains structs defining Avro's logical types
+use std::collections::{BTreeMap, HashMap};
+
+mod canonical;
 mod de;
+mod fingerprint;
 mod se;
+mod validate;
+
+pub use validate::Violation;
 
 /// An Avro Schema. It describes all _physical_ and _logical_ types.
 /// See [the spec](https://avro.apache.org/docs/current/spec.html) for details.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Schema {
     /// A null type
-    Null,
+    Null(Option<NullLogical>),
     /// Boolean (physically represented as a single byte)
-    Boolean,
+    Boolean(Option<BooleanLogical>),
     /// 32 bit signed integer (physically represented as a zigzag encoded variable number of bytes)
     Int(Option<IntLogical>),
     /// 64 bit signed integer (physically represented as a zigzag encoded variable number of bytes)
     Long(Option<LongLogical>),
     /// 32 bit float (physically represented as 4 bytes in little endian)
-    Float,
+    Float(Option<FloatLogical>),
     /// 64 bit float (physically represented as 8 bytes in little endian)
-    Double,
+    Double(Option<DoubleLogical>),
     /// variable length bytes (physically represented by a zigzag encoded positive integer followed by its number of bytes)
     Bytes(Option<BytesLogical>),
     /// variable length utf8 (physically represented by a zigzag encoded positive integer followed by its number of bytes)
@@ -34,6 +41,109 @@ pub enum Schema {
     Union(Vec<Schema>),
     /// todo
     Fixed(Fixed),
+    /// A reference, by its fullname, to a [`Schema::Record`], [`Schema::Enum`] or [`Schema::Fixed`]
+    /// declared elsewhere in the schema
+    Ref(String),
+    /// A primitive type, [`Schema::Array`] or [`Schema::Map`] with custom attributes
+    /// (e.g. `{"type": "string", "avro.java.string": "String"}`), which do not change how data is read.
+    Annotated(Box<Schema>, BTreeMap<String, serde_json::Value>),
+}
+
+impl Schema {
+    /// Returns all named types ([`Schema::Record`], [`Schema::Enum`] and [`Schema::Fixed`])
+    /// declared in this schema, by their fullname.
+    /// These are the types that a [`Schema::Ref`] refers to.
+    pub fn named_types(&self) -> HashMap<String, &Schema> {
+        let mut names = HashMap::new();
+        collect_named_types(self, None, &mut names);
+        names
+    }
+
+    /// Returns the name of this schema's type, used in error messages: the name of named types
+    /// and references, and the type otherwise (e.g. `long`).
+    pub(crate) fn type_name(&self) -> &str {
+        match self {
+            Schema::Null(_) => "null",
+            Schema::Boolean(_) => "boolean",
+            Schema::Int(_) => "int",
+            Schema::Long(_) => "long",
+            Schema::Float(_) => "float",
+            Schema::Double(_) => "double",
+            Schema::Bytes(_) => "bytes",
+            Schema::String(_) => "string",
+            Schema::Record(record) => &record.name,
+            Schema::Enum(enum_) => &enum_.name,
+            Schema::Array(_) => "array",
+            Schema::Map(_) => "map",
+            Schema::Union(_) => "union",
+            Schema::Fixed(fixed) => &fixed.name,
+            Schema::Ref(name) => name,
+            Schema::Annotated(schema, _) => schema.type_name(),
+        }
+    }
+}
+
+fn collect_named_types<'a>(
+    schema: &'a Schema,
+    namespace: Option<&str>,
+    names: &mut HashMap<String, &'a Schema>,
+) {
+    match schema {
+        Schema::Record(record) => {
+            let name = fullname(&record.name, record.namespace.as_deref().or(namespace));
+            let namespace = name
+                .rsplit_once('.')
+                .map(|(namespace, _)| namespace.to_string());
+            names.insert(name, schema);
+            for field in &record.fields {
+                collect_named_types(&field.schema, namespace.as_deref(), names);
+            }
+        }
+        Schema::Enum(Enum {
+            name,
+            namespace: ns,
+            ..
+        })
+        | Schema::Fixed(Fixed {
+            name,
+            namespace: ns,
+            ..
+        }) => {
+            names.insert(fullname(name, ns.as_deref().or(namespace)), schema);
+        }
+        Schema::Array(schema) | Schema::Map(schema) | Schema::Annotated(schema, _) => {
+            collect_named_types(schema, namespace, names)
+        }
+        Schema::Union(schemas) => {
+            for schema in schemas {
+                collect_named_types(schema, namespace, names)
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the fullname of a named type declared with `name` within `namespace`.
+/// A `name` containing a dot is already a fullname, in which case `namespace` is ignored.
+pub fn fullname(name: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) if !name.contains('.') && !namespace.is_empty() => {
+            format!("{}.{}", namespace, name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Joins the segments of a path within a schema, e.g. `["User", "tags", "[0]"]` into `User.tags[0]`
+pub(crate) fn join_path(segments: &[String]) -> String {
+    let mut path = String::new();
+    for segment in segments {
+        if !path.is_empty() && !segment.starts_with('[') {
+            path.push('.');
+        }
+        path.push_str(segment);
+    }
+    path
 }
 
 /// Order of a [`Field`].
@@ -57,12 +167,14 @@ pub struct Field {
     pub doc: Option<String>,
     /// Its Schema
     pub schema: Schema,
-    /// Its default value
-    pub default: Option<Schema>,
+    /// Its default value, as JSON interpreted according to its schema (e.g. `0` for an int)
+    pub default: Option<serde_json::Value>,
     /// Its optional order
     pub order: Option<Order>,
     /// Its aliases
     pub aliases: Vec<String>,
+    /// Its custom attributes
+    pub properties: BTreeMap<String, serde_json::Value>,
 }
 
 impl Field {
@@ -75,6 +187,7 @@ impl Field {
             default: None,
             order: None,
             aliases: vec![],
+            properties: BTreeMap::new(),
         }
     }
 }
@@ -92,6 +205,8 @@ pub struct Record {
     pub aliases: Vec<String>,
     /// Its children fields
     pub fields: Vec<Field>,
+    /// Its custom attributes
+    pub properties: BTreeMap<String, serde_json::Value>,
 }
 
 impl Record {
@@ -103,6 +218,7 @@ impl Record {
             doc: None,
             fields,
             aliases: vec![],
+            properties: BTreeMap::new(),
         }
     }
 }
@@ -122,6 +238,8 @@ pub struct Fixed {
     pub size: usize,
     /// Its optional logical type
     pub logical: Option<FixedLogical>,
+    /// Its custom attributes
+    pub properties: BTreeMap<String, serde_json::Value>,
 }
 
 impl Fixed {
@@ -134,6 +252,7 @@ impl Fixed {
             size,
             aliases: vec![],
             logical: None,
+            properties: BTreeMap::new(),
         }
     }
 }
@@ -153,6 +272,8 @@ pub struct Enum {
     pub symbols: Vec<String>,
     /// Its default symbol
     pub default: Option<String>,
+    /// Its custom attributes
+    pub properties: BTreeMap<String, serde_json::Value>,
 }
 
 impl Enum {
@@ -165,6 +286,7 @@ impl Enum {
             symbols,
             aliases: vec![],
             default: None,
+            properties: BTreeMap::new(),
         }
     }
 }
@@ -187,17 +309,47 @@ impl From<Fixed> for Schema {
     }
 }
 
+/// Enum of all logical types of [`Schema::Null`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NullLogical {
+    /// A logical type unknown to this crate, by name
+    Other(String),
+}
+
+/// Enum of all logical types of [`Schema::Boolean`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BooleanLogical {
+    /// A logical type unknown to this crate, by name
+    Other(String),
+}
+
+/// Enum of all logical types of [`Schema::Float`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FloatLogical {
+    /// A logical type unknown to this crate, by name
+    Other(String),
+}
+
+/// Enum of all logical types of [`Schema::Double`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DoubleLogical {
+    /// A logical type unknown to this crate, by name
+    Other(String),
+}
+
 /// Enum of all logical types of [`Schema::Int`]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum IntLogical {
     /// A date
     Date,
     /// A time
     Time,
+    /// A logical type unknown to this crate (or with invalid parameters), by name
+    Other(String),
 }
 
 /// Enum of all logical types of [`Schema::Long`]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LongLogical {
     /// A time
     Time,
@@ -209,27 +361,35 @@ pub enum LongLogical {
     LocalTimestampMillis,
     /// A timestamp without timezone
     LocalTimestampMicros,
+    /// A logical type unknown to this crate (or with invalid parameters), by name
+    Other(String),
 }
 
 /// Enum of all logical types of [`Schema::String`]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum StringLogical {
     /// A UUID
     Uuid,
+    /// A logical type unknown to this crate (or with invalid parameters), by name
+    Other(String),
 }
 
 /// Enum of all logical types of [`Schema::Fixed`]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FixedLogical {
     /// A decimal
     Decimal(usize, usize),
     /// A duration
     Duration,
+    /// A logical type unknown to this crate (or with invalid parameters), by name
+    Other(String),
 }
 
 /// Enum of all logical types of [`Schema::Bytes`]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BytesLogical {
     /// A decimal
     Decimal(usize, usize),
+    /// A logical type unknown to this crate (or with invalid parameters), by name
+    Other(String),
 }
@@ -9,8 +9,14 @@ impl Serialize for Schema {
         S: Serializer,
     {
         match self {
-            Schema::Null => serializer.serialize_str("null"),
-            Schema::Boolean => serializer.serialize_str("boolean"),
+            Schema::Null(logical) => match logical {
+                None => serializer.serialize_str("null"),
+                Some(NullLogical::Other(name)) => serialize_other(serializer, "null", name),
+            },
+            Schema::Boolean(logical) => match logical {
+                None => serializer.serialize_str("boolean"),
+                Some(BooleanLogical::Other(name)) => serialize_other(serializer, "boolean", name),
+            },
             Schema::Int(logical) => match logical {
                 None => serializer.serialize_str("int"),
                 Some(logical) => {
@@ -19,6 +25,7 @@ impl Serialize for Schema {
                     let name = match logical {
                         IntLogical::Date => "date",
                         IntLogical::Time => "time-millis",
+                        IntLogical::Other(name) => name,
                     };
                     map.serialize_entry("logicalType", name)?;
                     map.end()
@@ -35,13 +42,20 @@ impl Serialize for Schema {
                         LongLogical::TimestampMicros => "timestamp-micros",
                         LongLogical::LocalTimestampMillis => "local-timestamp-millis",
                         LongLogical::LocalTimestampMicros => "local-timestamp-micros",
+                        LongLogical::Other(name) => name,
                     };
                     map.serialize_entry("logicalType", name)?;
                     map.end()
                 }
             },
-            Schema::Float => serializer.serialize_str("float"),
-            Schema::Double => serializer.serialize_str("double"),
+            Schema::Float(logical) => match logical {
+                None => serializer.serialize_str("float"),
+                Some(FloatLogical::Other(name)) => serialize_other(serializer, "float", name),
+            },
+            Schema::Double(logical) => match logical {
+                None => serializer.serialize_str("double"),
+                Some(DoubleLogical::Other(name)) => serialize_other(serializer, "double", name),
+            },
             Schema::Bytes(logical) => match logical {
                 None => serializer.serialize_str("bytes"),
                 Some(logical) => match logical {
@@ -55,6 +69,12 @@ impl Serialize for Schema {
                         }
                         map.end()
                     }
+                    BytesLogical::Other(name) => {
+                        let mut map = serializer.serialize_map(Some(2))?;
+                        map.serialize_entry("type", "bytes")?;
+                        map.serialize_entry("logicalType", name)?;
+                        map.end()
+                    }
                 },
             },
             Schema::String(logical) => match logical {
@@ -66,6 +86,12 @@ impl Serialize for Schema {
                         map.serialize_entry("logicalType", "uuid")?;
                         map.end()
                     }
+                    StringLogical::Other(name) => {
+                        let mut map = serializer.serialize_map(Some(2))?;
+                        map.serialize_entry("type", "string")?;
+                        map.serialize_entry("logicalType", name)?;
+                        map.end()
+                    }
                 },
             },
             Schema::Record(record) => {
@@ -75,6 +101,7 @@ impl Serialize for Schema {
                     doc,
                     aliases,
                     fields,
+                    properties,
                 } = record;
                 let mut map = serializer.serialize_map(Some(3))?;
                 map.serialize_entry("type", "record")?;
@@ -89,6 +116,7 @@ impl Serialize for Schema {
                     map.serialize_entry("doc", doc)?;
                 }
                 map.serialize_entry("fields", fields)?;
+                serialize_properties(&mut map, properties)?;
                 map.end()
             }
             Schema::Enum(enum_) => {
@@ -99,6 +127,7 @@ impl Serialize for Schema {
                     doc,
                     symbols,
                     default,
+                    properties,
                 } = enum_;
                 let mut map = serializer.serialize_map(Some(3))?;
                 map.serialize_entry("type", "enum")?;
@@ -116,6 +145,7 @@ impl Serialize for Schema {
                     map.serialize_entry("default", default)?;
                 }
                 map.serialize_entry("symbols", symbols)?;
+                serialize_properties(&mut map, properties)?;
                 map.end()
             }
             Schema::Array(schema) => {
@@ -145,6 +175,7 @@ impl Serialize for Schema {
                     aliases,
                     size,
                     logical,
+                    properties,
                 } = fixed;
 
                 let mut map = serializer.serialize_map(None)?;
@@ -171,9 +202,26 @@ impl Serialize for Schema {
                             }
                         }
                         FixedLogical::Duration => map.serialize_entry("logicalType", "duration")?,
+                        FixedLogical::Other(name) => map.serialize_entry("logicalType", name)?,
                     }
                 }
-
+                serialize_properties(&mut map, properties)?;
+                map.end()
+            }
+            Schema::Ref(name) => serializer.serialize_str(name),
+            Schema::Annotated(schema, properties) => {
+                // the annotated type is either a string (e.g. `"int"`) or a map (e.g. of an array)
+                let schema = serde_json::to_value(schema).map_err(serde::ser::Error::custom)?;
+                let mut map = serializer.serialize_map(None)?;
+                match schema {
+                    serde_json::Value::Object(entries) => {
+                        for (key, value) in &entries {
+                            map.serialize_entry(key, value)?;
+                        }
+                    }
+                    type_ => map.serialize_entry("type", &type_)?,
+                }
+                serialize_properties(&mut map, properties)?;
                 map.end()
             }
         }
@@ -192,6 +240,7 @@ impl Serialize for Field {
             default,
             order,
             aliases,
+            properties,
         } = self;
 
         let mut map = serializer.serialize_map(None)?;
@@ -214,7 +263,29 @@ impl Serialize for Field {
             };
             map.serialize_entry("order", order)?;
         }
-
+        serialize_properties(&mut map, properties)?;
         map.end()
     }
 }
+
+fn serialize_properties<M: SerializeMap>(
+    map: &mut M,
+    properties: &BTreeMap<String, serde_json::Value>,
+) -> Result<(), M::Error> {
+    for (key, value) in properties {
+        map.serialize_entry(key, value)?;
+    }
+    Ok(())
+}
+
+/// Serializes a primitive `type_` with a logical type unknown to this crate
+fn serialize_other<S: Serializer>(
+    serializer: S,
+    type_: &str,
+    name: &str,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry("type", type_)?;
+    map.serialize_entry("logicalType", name)?;
+    map.end()
+}
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use crate::error::Error;
+
+use super::*;
+
+/// A violation of the spec by a [`Schema`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Violation {
+    /// The location of the violation, e.g. `User.tags.items`
+    pub path: String,
+    /// A description of the violation
+    pub reason: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+impl Schema {
+    /// Validates this schema against the rules of the spec that deserialization does not enforce:
+    /// names must match `[A-Za-z_][A-Za-z0-9_]*`, field names and enum symbols must be unique,
+    /// unions must not be nested nor contain two types of the same kind (or name), enum defaults
+    /// must be symbols and decimals must have `scale <= precision`.
+    /// # Error
+    /// This function errors with [`Error::SchemaViolations`] and every violation found.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut validator = Validator {
+            path: vec![self.type_name().to_string()],
+            violations: vec![],
+        };
+        validator.validate(self, None);
+        if validator.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::SchemaViolations(validator.violations))
+        }
+    }
+
+    /// Deserializes a schema from its JSON representation, [validating](Schema::validate) it
+    /// when `validate` is true.
+    pub fn from_json(json: &str, validate: bool) -> Result<Self, Error> {
+        let schema: Schema = serde_json::from_str(json).map_err(Error::InvalidSchema)?;
+        if validate {
+            schema.validate()?;
+        }
+        Ok(schema)
+    }
+}
+
+struct Validator {
+    path: Vec<String>,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn violation(&mut self, reason: String) {
+        self.violations.push(Violation {
+            path: join_path(&self.path),
+            reason,
+        });
+    }
+
+    /// Validates the name of a named type and returns its fullname
+    fn name(&mut self, name: &str, namespace: Option<&str>, enclosing: Option<&str>) -> String {
+        if !name.split('.').all(is_valid_name) {
+            self.violation(format!("\"{}\" is not a valid name", name));
+        }
+        if let Some(namespace) = namespace {
+            if !namespace.is_empty() && !namespace.split('.').all(is_valid_name) {
+                self.violation(format!("\"{}\" is not a valid namespace", namespace));
+            }
+        }
+        fullname(name, namespace.or(enclosing))
+    }
+
+    fn validate(&mut self, schema: &Schema, namespace: Option<&str>) {
+        match schema {
+            Schema::Bytes(Some(BytesLogical::Decimal(precision, scale))) => {
+                self.decimal(*precision, *scale)
+            }
+            Schema::Record(record) => {
+                let name = self.name(&record.name, record.namespace.as_deref(), namespace);
+                let namespace = name.rsplit_once('.').map(|(namespace, _)| namespace);
+
+                let mut names = HashSet::new();
+                for field in &record.fields {
+                    self.path.push(field.name.clone());
+                    if !is_valid_name(&field.name) {
+                        self.violation(format!("\"{}\" is not a valid field name", field.name));
+                    }
+                    if !names.insert(field.name.as_str()) {
+                        self.violation(format!(
+                            "the field \"{}\" is defined more than once",
+                            field.name
+                        ));
+                    }
+                    self.validate(&field.schema, namespace);
+                    self.path.pop();
+                }
+            }
+            Schema::Enum(enum_) => {
+                self.name(&enum_.name, enum_.namespace.as_deref(), namespace);
+                let mut symbols = HashSet::new();
+                for symbol in &enum_.symbols {
+                    if !is_valid_name(symbol) {
+                        self.violation(format!("\"{}\" is not a valid symbol", symbol));
+                    }
+                    if !symbols.insert(symbol.as_str()) {
+                        self.violation(format!(
+                            "the symbol \"{}\" is defined more than once",
+                            symbol
+                        ));
+                    }
+                }
+                if let Some(default) = &enum_.default {
+                    if !symbols.contains(default.as_str()) {
+                        self.violation(format!("the default \"{}\" is not a symbol", default));
+                    }
+                }
+            }
+            Schema::Fixed(fixed) => {
+                self.name(&fixed.name, fixed.namespace.as_deref(), namespace);
+                if let Some(FixedLogical::Decimal(precision, scale)) = fixed.logical {
+                    self.decimal(precision, scale)
+                }
+            }
+            Schema::Array(item) => {
+                self.path.push("items".to_string());
+                self.validate(item, namespace);
+                self.path.pop();
+            }
+            Schema::Map(item) => {
+                self.path.push("values".to_string());
+                self.validate(item, namespace);
+                self.path.pop();
+            }
+            Schema::Union(schemas) => {
+                let mut types = HashSet::new();
+                for (index, schema) in schemas.iter().enumerate() {
+                    self.path.push(format!("[{}]", index));
+                    if let Schema::Union(_) = schema {
+                        self.violation("unions must not contain unions".to_string());
+                    } else if let Some(name) = variant_name(schema, namespace) {
+                        if !types.insert(name.clone()) {
+                            self.violation(format!("the union contains {} more than once", name));
+                        }
+                    }
+                    self.validate(schema, namespace);
+                    self.path.pop();
+                }
+            }
+            Schema::Annotated(schema, _) => self.validate(schema, namespace),
+            _ => {}
+        }
+    }
+
+    fn decimal(&mut self, precision: usize, scale: usize) {
+        if scale > precision {
+            self.violation(format!(
+                "the scale of the decimal ({}) is larger than its precision ({})",
+                scale, precision
+            ));
+        }
+    }
+}
+
+/// Whether `name` matches `[A-Za-z_][A-Za-z0-9_]*`
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns the name by which a variant of a union is distinguished from the others: its fullname
+/// for named types and its (physical) type otherwise.
+fn variant_name(schema: &Schema, namespace: Option<&str>) -> Option<String> {
+    Some(match schema {
+        Schema::Record(Record {
+            name,
+            namespace: ns,
+            ..
+        })
+        | Schema::Enum(Enum {
+            name,
+            namespace: ns,
+            ..
+        })
+        | Schema::Fixed(Fixed {
+            name,
+            namespace: ns,
+            ..
+        }) => format!("\"{}\"", fullname(name, ns.as_deref().or(namespace))),
+        Schema::Ref(name) => format!("\"{}\"", name),
+        Schema::Union(_) => return None,
+        Schema::Annotated(schema, _) => return variant_name(schema, namespace),
+        schema => schema.type_name().to_string(),
+    })
+}
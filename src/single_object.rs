@@ -0,0 +1,61 @@
+//! APIs to read and write Avro's [single-object encoding](https://avro.apache.org/docs/current/spec.html#single_object_encoding):
+//! the marker `C3 01`, followed by the 8-byte little-endian [Rabin fingerprint](Schema::fingerprint_rabin)
+//! of the schema, followed by the Avro data of a single value.
+use std::io::{Read, Write};
+
+use crate::error::Error;
+use crate::read::Decoder;
+use crate::schema::Schema;
+use crate::store::SchemaStore;
+use crate::value::Value;
+use crate::write::Encoder;
+
+/// The marker at the start of every single-object encoded message
+pub const MARKER: [u8; 2] = [0xC3, 0x01];
+
+/// Writes the header of a single-object encoded message of the schema with `fingerprint`.
+pub fn write_header<W: Write>(writer: &mut W, fingerprint: u64) -> Result<(), Error> {
+    writer.write_all(&MARKER)?;
+    writer.write_all(&fingerprint.to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads the header of a single-object encoded message, returning the fingerprint of its schema.
+/// # Error
+/// This function errors iff the message does not start with [`MARKER`] or is shorter than the header.
+pub fn read_header<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut marker = [0u8; 2];
+    reader.read_exact(&mut marker)?;
+    if marker != MARKER {
+        return Err(Error::InvalidSingleObjectMarker(marker));
+    }
+    let mut fingerprint = [0u8; 8];
+    reader.read_exact(&mut fingerprint)?;
+    Ok(u64::from_le_bytes(fingerprint))
+}
+
+/// Writes `value` of `schema` as a single-object encoded message.
+/// # Error
+/// This function errors iff `value` is not described by `schema`.
+pub fn encode<W: Write>(value: &Value, schema: &Schema, writer: &mut W) -> Result<(), Error> {
+    write_header(writer, schema.fingerprint_rabin())?;
+    Encoder::new(schema).encode(value, writer)
+}
+
+/// Reads a single-object encoded message, whose schema is resolved by its
+/// [Rabin fingerprint](Schema::fingerprint_rabin) from `store`.
+/// Returns the value and its schema.
+/// # Error
+/// This function errors iff the message has an invalid header, its fingerprint is not in `store`
+/// or its data is not described by the schema.
+pub fn decode<'a, R: Read, S: SchemaStore<u64>>(
+    reader: &mut R,
+    store: &'a S,
+) -> Result<(Value, &'a Schema), Error> {
+    let fingerprint = read_header(reader)?;
+    let schema = store
+        .get(&fingerprint)
+        .ok_or(Error::UnknownFingerprint(fingerprint))?;
+    let value = Decoder::new(schema).decode(reader)?;
+    Ok((value, schema))
+}
@@ -0,0 +1,21 @@
+//! Contains [`SchemaStore`], used to resolve the schemas of framed messages
+//! (e.g. [single-object encoding](crate::single_object) or [Confluent's](crate::confluent)).
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use crate::schema::Schema;
+
+/// A store of [`Schema`]s identified by keys of type `K`, such as fingerprints or the ids
+/// of a schema registry.
+///
+/// [`HashMap`] implements this trait and can be used as an in-memory store.
+pub trait SchemaStore<K> {
+    /// Returns the schema identified by `key`, if any.
+    fn get(&self, key: &K) -> Option<&Schema>;
+}
+
+impl<K: Hash + Eq, S: BuildHasher> SchemaStore<K> for HashMap<K, Schema, S> {
+    fn get(&self, key: &K) -> Option<&Schema> {
+        HashMap::get(self, key)
+    }
+}
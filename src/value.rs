@@ -0,0 +1,171 @@
+//! Contains [`Value`]
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::schema::{Field, Schema};
+
+/// A dynamically typed Avro datum, whose type is described by a [`Schema`].
+/// Logical types are represented by their physical type (e.g. a date is a [`Value::Int`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A null
+    Null,
+    /// A boolean
+    Boolean(bool),
+    /// A 32 bit signed integer
+    Int(i32),
+    /// A 64 bit signed integer
+    Long(i64),
+    /// A 32 bit float
+    Float(f32),
+    /// A 64 bit float
+    Double(f64),
+    /// A sequence of bytes
+    Bytes(Vec<u8>),
+    /// An utf8 string
+    String(String),
+    /// A record, as its fields' names and values, in the order of the schema's fields
+    Record(Vec<(String, Value)>),
+    /// The symbol of an enum
+    Enum(String),
+    /// An array of values of the same type
+    Array(Vec<Value>),
+    /// A map String -> value
+    Map(HashMap<String, Value>),
+    /// A value of a union, together with the index of the union's variant it belongs to
+    Union(usize, Box<Value>),
+    /// A sequence of bytes of known size
+    Fixed(Vec<u8>),
+}
+
+/// Returns the [`Value`] of the default `json` of a field of `schema`, following the spec:
+/// bytes and fixed are strings whose code points are the bytes, and unions are of their first variant.
+pub(crate) fn from_default<S: Borrow<Schema>>(
+    json: &serde_json::Value,
+    schema: &Schema,
+    names: &HashMap<String, S>,
+) -> Result<Value, String> {
+    from_json(json, schema, names, &mut vec![])
+}
+
+/// [`from_default`], where `defaults` are the fields whose default is being read, in which
+/// a field missing from a record's default falls back to its own default.
+fn from_json<S: Borrow<Schema>>(
+    json: &serde_json::Value,
+    schema: &Schema,
+    names: &HashMap<String, S>,
+    defaults: &mut Vec<*const Field>,
+) -> Result<Value, String> {
+    use serde_json::Value as Json;
+    Ok(match (schema, json) {
+        (Schema::Null(_), Json::Null) => Value::Null,
+        (Schema::Boolean(_), Json::Bool(value)) => Value::Boolean(*value),
+        (Schema::Int(_), Json::Number(number)) => Value::Int(
+            number
+                .as_i64()
+                .and_then(|x| i32::try_from(x).ok())
+                .ok_or_else(|| format!("{} is not an int", number))?,
+        ),
+        (Schema::Long(_), Json::Number(number)) => Value::Long(
+            number
+                .as_i64()
+                .ok_or_else(|| format!("{} is not a long", number))?,
+        ),
+        (Schema::Float(_), Json::Number(number)) => Value::Float(
+            number
+                .as_f64()
+                .ok_or_else(|| format!("{} is not a float", number))? as f32,
+        ),
+        (Schema::Double(_), Json::Number(number)) => Value::Double(
+            number
+                .as_f64()
+                .ok_or_else(|| format!("{} is not a double", number))?,
+        ),
+        (Schema::Bytes(_), Json::String(value)) => Value::Bytes(to_bytes(value)?),
+        (Schema::String(_), Json::String(value)) => Value::String(value.clone()),
+        (Schema::Record(record), Json::Object(values)) => Value::Record(
+            record
+                .fields
+                .iter()
+                .map(|field| {
+                    if let Some(json) = values.get(&field.name) {
+                        let value = from_json(json, &field.schema, names, defaults)?;
+                        return Ok((field.name.clone(), value));
+                    }
+                    let json = field
+                        .default
+                        .as_ref()
+                        .ok_or_else(|| format!("the field \"{}\" is missing", field.name))?;
+                    // a default missing its own field would be completed by itself forever
+                    let key = field as *const Field;
+                    if defaults.contains(&key) {
+                        return Err(format!(
+                            "the field \"{}\" is missing from its own default",
+                            field.name
+                        ));
+                    }
+                    defaults.push(key);
+                    let value = from_json(json, &field.schema, names, defaults);
+                    defaults.pop();
+                    Ok((field.name.clone(), value?))
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+        (Schema::Enum(enum_), Json::String(symbol)) => {
+            if !enum_.symbols.contains(symbol) {
+                return Err(format!(
+                    "\"{}\" is not a symbol of the enum \"{}\"",
+                    symbol, enum_.name
+                ));
+            }
+            Value::Enum(symbol.clone())
+        }
+        (Schema::Array(item), Json::Array(values)) => Value::Array(
+            values
+                .iter()
+                .map(|json| from_json(json, item, names, defaults))
+                .collect::<Result<_, _>>()?,
+        ),
+        (Schema::Map(item), Json::Object(values)) => Value::Map(
+            values
+                .iter()
+                .map(|(key, json)| Ok((key.clone(), from_json(json, item, names, defaults)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        (Schema::Union(schemas), json) => {
+            let schema = schemas
+                .first()
+                .ok_or_else(|| "the union has no variants".to_string())?;
+            Value::Union(0, Box::new(from_json(json, schema, names, defaults)?))
+        }
+        (Schema::Fixed(fixed), Json::String(value)) => {
+            let value = to_bytes(value)?;
+            if value.len() != fixed.size {
+                return Err(format!(
+                    "the fixed \"{}\" has {} bytes but the default has {}",
+                    fixed.name,
+                    fixed.size,
+                    value.len()
+                ));
+            }
+            Value::Fixed(value)
+        }
+        (Schema::Ref(name), json) => {
+            let schema = names
+                .get(name)
+                .ok_or_else(|| format!("the type \"{}\" is not defined", name))?;
+            from_json(json, schema.borrow(), names, defaults)?
+        }
+        (Schema::Annotated(schema, _), json) => from_json(json, schema, names, defaults)?,
+        (schema, json) => return Err(format!("{} is not a valid {}", json, schema.type_name())),
+    })
+}
+
+/// Returns the bytes of a default, a string whose code points are in `[0, 255]`
+fn to_bytes(value: &str) -> Result<Vec<u8>, String> {
+    value
+        .chars()
+        .map(|c| u8::try_from(c as u32).map_err(|_| format!("{:?} is not a byte", c)))
+        .collect()
+}
@@ -4,17 +4,21 @@ use crate::error::Error;
 
 use crate::file::CompressedBlock;
 
-use super::{encode::zigzag_encode, file::SYNC_NUMBER};
+use super::encode::zigzag_encode;
 
-/// Writes a [`CompressedBlock`] to `writer`
-pub fn write_block<W: Write>(writer: &mut W, block: &CompressedBlock) -> Result<(), Error> {
+/// Writes a [`CompressedBlock`] to `writer`, followed by the file's `marker`
+pub fn write_block<W: Write>(
+    writer: &mut W,
+    block: &CompressedBlock,
+    marker: [u8; 16],
+) -> Result<(), Error> {
     // write size and rows
     zigzag_encode(block.number_of_rows as i64, writer)?;
     zigzag_encode(block.data.len() as i64, writer)?;
 
     writer.write_all(&block.data)?;
 
-    writer.write_all(&SYNC_NUMBER)?;
+    writer.write_all(&marker)?;
 
     Ok(())
 }
@@ -12,6 +12,17 @@ pub fn compress(
     block: &mut Block,
     compressed: &mut CompressedBlock,
     compression: Option<Compression>,
+) -> Result<bool, Error> {
+    compress_with_level(block, compressed, compression, 0)
+}
+
+/// Compresses a [`Block`] to a [`CompressedBlock`] with the Zstandard compression `level`
+/// (0 means zstd's default level). Other codecs ignore `level`.
+pub fn compress_with_level(
+    block: &mut Block,
+    compressed: &mut CompressedBlock,
+    compression: Option<Compression>,
+    #[cfg_attr(not(feature = "zstd"), allow(unused_variables))] level: i32,
 ) -> Result<bool, Error> {
     compressed.number_of_rows = block.number_of_rows;
     let block = &mut block.data;
@@ -41,15 +52,47 @@ pub fn compress(
             compressed.resize(required_len, 0);
             let compressed_bytes = Encoder::new()
                 .compress(block, compressed)
-                .map_err(|_| Error::OutOfSpec)?;
+                .map_err(|error| Error::OutOfSpec(error.to_string()))?;
             compressed.truncate(compressed_bytes);
 
             compressed.extend(CRC_TABLE.checksum(block).to_be_bytes());
             Ok(false)
         }
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstandard) => {
+            compressed.clear();
+            zstd::stream::copy_encode(&block[..], compressed, level)?;
+            Ok(false)
+        }
+        #[cfg(feature = "bzip2")]
+        Some(Compression::Bzip2) => {
+            use std::io::Write;
+            compressed.clear();
+            let mut encoder =
+                bzip2::write::BzEncoder::new(compressed, bzip2::Compression::default());
+            encoder.write_all(block)?;
+            encoder.finish()?;
+            Ok(false)
+        }
+        #[cfg(feature = "xz2")]
+        Some(Compression::Xz) => {
+            use std::io::Write;
+            compressed.clear();
+            // 6 is the default preset of xz and of the reference implementation
+            let mut encoder = xz2::write::XzEncoder::new(compressed, 6);
+            encoder.write_all(block)?;
+            encoder.finish()?;
+            Ok(false)
+        }
         #[cfg(not(feature = "compression"))]
         Some(Compression::Deflate) => Err(Error::RequiresCompression),
         #[cfg(not(feature = "compression"))]
         Some(Compression::Snappy) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "zstd"))]
+        Some(Compression::Zstandard) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "bzip2"))]
+        Some(Compression::Bzip2) => Err(Error::RequiresCompression),
+        #[cfg(not(feature = "xz2"))]
+        Some(Compression::Xz) => Err(Error::RequiresCompression),
     }
 }
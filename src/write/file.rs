@@ -1,29 +1,47 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::error::Error;
-use crate::file::Compression;
-use crate::schema::{Record, Schema};
+use crate::file::{Compression, FileMetadata};
+use crate::schema::Schema;
 
 use super::encode;
 
-pub(crate) const SYNC_NUMBER: [u8; 16] = [1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4];
 // * Four bytes, ASCII 'O', 'b', 'j', followed by 1.
 pub(crate) const AVRO_MAGIC: [u8; 4] = [b'O', b'b', b'j', 1u8];
 
-/// Serializes an [`Schema`] and optional [`Compression`] into an avro header.
+/// Serializes an [`Schema`], optional [`Compression`] and user metadata into an avro header.
 fn serialize_header(
     schema: &Schema,
     compression: Option<Compression>,
+    metadata: &BTreeMap<String, Vec<u8>>,
 ) -> Result<HashMap<String, Vec<u8>>, Error> {
-    let schema = serde_json::to_string(schema).map_err(|_| Error::OutOfSpec)?;
+    let schema = serde_json::to_string(schema).map_err(Error::InvalidSchema)?;
 
     let mut header = HashMap::<String, Vec<u8>>::default();
 
+    for (key, value) in metadata {
+        // these are written from `schema` and `compression`, so that read metadata can be written back
+        if key == "avro.schema" || key == "avro.codec" {
+            continue;
+        }
+        // other keys in the `avro.` namespace are reserved by the spec
+        if key.starts_with("avro.") {
+            return Err(Error::OutOfSpec(format!(
+                "the metadata key \"{}\" is reserved",
+                key
+            )));
+        }
+        header.insert(key.clone(), value.clone());
+    }
+
     header.insert("avro.schema".to_string(), schema.into_bytes());
     if let Some(compression) = compression {
         let value = match compression {
             Compression::Snappy => b"snappy".to_vec(),
             Compression::Deflate => b"deflate".to_vec(),
+            Compression::Zstandard => b"zstandard".to_vec(),
+            Compression::Bzip2 => b"bzip2".to_vec(),
+            Compression::Xz => b"xz".to_vec(),
         };
         header.insert("avro.codec".to_string(), value);
     };
@@ -34,28 +52,24 @@ fn serialize_header(
 /// Writes Avro's metadata to `writer`.
 pub fn write_metadata<W: std::io::Write>(
     writer: &mut W,
-    record: Record,
-    compression: Option<Compression>,
+    metadata: &FileMetadata,
 ) -> Result<(), Error> {
     writer.write_all(&AVRO_MAGIC)?;
 
     // * file metadata, including the schema.
-    let schema = Schema::Record(record);
-
-    write_schema(writer, &schema, compression)?;
+    write_header(writer, metadata)?;
 
     // The 16-byte, randomly-generated sync marker for this file.
-    writer.write_all(&SYNC_NUMBER)?;
+    writer.write_all(&metadata.marker)?;
 
     Ok(())
 }
 
-pub(crate) fn write_schema<W: std::io::Write>(
+pub(crate) fn write_header<W: std::io::Write>(
     writer: &mut W,
-    schema: &Schema,
-    compression: Option<Compression>,
+    metadata: &FileMetadata,
 ) -> Result<(), Error> {
-    let header = serialize_header(schema, compression)?;
+    let header = serialize_header(&metadata.schema, metadata.compression, &metadata.metadata)?;
 
     encode::zigzag_encode(header.len() as i64, writer)?;
     for (name, item) in header {
@@ -1,8 +1,12 @@
 //! Functions to compress and write Files' metadata and blocks
 mod compression;
-pub use compression::compress;
+pub use compression::{compress, compress_with_level};
 mod block;
 pub mod encode;
 pub(crate) mod file;
+mod serialize;
+mod writer;
 pub use block::write_block;
 pub use file::write_metadata;
+pub use serialize::Encoder;
+pub use writer::{FileWriter, WriteOptions};
@@ -0,0 +1,297 @@
+//! APIs to serialize [`Value`]s into Avro data.
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::error::Error;
+use crate::file::Block;
+use crate::schema::{BytesLogical, FixedLogical, IntLogical, LongLogical, Schema, StringLogical};
+use crate::value::Value;
+
+use super::encode::{write_binary, zigzag_encode};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Serializes [`Value`]s into Avro data described by a [`Schema`].
+#[derive(Debug, Clone)]
+pub struct Encoder<'a> {
+    schema: &'a Schema,
+    names: HashMap<String, &'a Schema>,
+}
+
+impl<'a> Encoder<'a> {
+    /// Returns a new [`Encoder`] of `schema`.
+    pub fn new(schema: &'a Schema) -> Self {
+        Self {
+            schema,
+            names: schema.named_types(),
+        }
+    }
+
+    /// Encodes `value` to `writer`.
+    /// # Error
+    /// This function errors iff `value` is not described by this encoder's schema, in which
+    /// case `writer` may have been partially written to.
+    pub fn encode<W: Write>(&self, value: &Value, writer: &mut W) -> Result<(), Error> {
+        encode(value, self.schema, &self.names, writer)
+    }
+
+    /// Appends `value` as a new row of `block`.
+    /// # Error
+    /// This function errors iff `value` is not described by this encoder's schema, in which
+    /// case `block` is left unchanged.
+    pub fn encode_block(&self, value: &Value, block: &mut Block) -> Result<(), Error> {
+        encode_block(value, self.schema, &self.names, block)
+    }
+}
+
+/// Appends `value` as a new row of `block`, leaving `block` unchanged on error.
+pub(crate) fn encode_block<S: Borrow<Schema>>(
+    value: &Value,
+    schema: &Schema,
+    names: &HashMap<String, S>,
+    block: &mut Block,
+) -> Result<(), Error> {
+    let len = block.data.len();
+    if let Err(error) = encode(value, schema, names, &mut block.data) {
+        block.data.truncate(len);
+        return Err(error);
+    }
+    block.number_of_rows += 1;
+    Ok(())
+}
+
+/// Encodes `value` of `schema` to `writer`, where `names` are the named types of the schema.
+fn encode<W: Write, S: Borrow<Schema>>(
+    value: &Value,
+    schema: &Schema,
+    names: &HashMap<String, S>,
+    writer: &mut W,
+) -> Result<(), Error> {
+    match (schema, value) {
+        (Schema::Null(_), Value::Null) => Ok(()),
+        (Schema::Boolean(_), Value::Boolean(value)) => {
+            writer.write_all(&[*value as u8])?;
+            Ok(())
+        }
+        (Schema::Int(logical), Value::Int(value)) => {
+            if let Some(IntLogical::Time) = logical {
+                check_range(*value as i64, MILLIS_PER_DAY)?;
+            }
+            zigzag_encode(*value as i64, writer)
+        }
+        (Schema::Long(logical), Value::Long(value)) => {
+            if let Some(LongLogical::Time) = logical {
+                check_range(*value, MILLIS_PER_DAY * 1000)?;
+            }
+            zigzag_encode(*value, writer)
+        }
+        (Schema::Float(_), Value::Float(value)) => {
+            writer.write_all(&value.to_le_bytes())?;
+            Ok(())
+        }
+        (Schema::Double(_), Value::Double(value)) => {
+            writer.write_all(&value.to_le_bytes())?;
+            Ok(())
+        }
+        (Schema::Bytes(logical), Value::Bytes(value)) => {
+            if let Some(BytesLogical::Decimal(precision, _)) = logical {
+                check_decimal(value, *precision)?;
+            }
+            write_binary(value, writer)
+        }
+        (Schema::String(logical), Value::String(value)) => {
+            if let Some(StringLogical::Uuid) = logical {
+                check_uuid(value)?;
+            }
+            write_binary(value.as_bytes(), writer)
+        }
+        (Schema::Record(record), Value::Record(values)) => {
+            if record.fields.len() != values.len() {
+                return Err(Error::InvalidValue(format!(
+                    "the record \"{}\" has {} fields but the value has {}",
+                    record.name,
+                    record.fields.len(),
+                    values.len()
+                )));
+            }
+            for field in &record.fields {
+                let value = values
+                    .iter()
+                    .find(|(name, _)| name == &field.name)
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| {
+                        Error::InvalidValue(format!(
+                            "the field \"{}\" of the record \"{}\" is missing",
+                            field.name, record.name
+                        ))
+                    })?;
+                encode(value, &field.schema, names, writer)?;
+            }
+            Ok(())
+        }
+        (Schema::Enum(enum_), Value::Enum(symbol)) => {
+            let index = enum_
+                .symbols
+                .iter()
+                .position(|x| x == symbol)
+                .ok_or_else(|| {
+                    Error::InvalidValue(format!(
+                        "\"{}\" is not a symbol of the enum \"{}\"",
+                        symbol, enum_.name
+                    ))
+                })?;
+            zigzag_encode(index as i64, writer)
+        }
+        (Schema::Array(item), Value::Array(values)) => {
+            if !values.is_empty() {
+                zigzag_encode(values.len() as i64, writer)?;
+                for value in values {
+                    encode(value, item, names, writer)?;
+                }
+            }
+            zigzag_encode(0, writer)
+        }
+        (Schema::Map(item), Value::Map(values)) => {
+            if !values.is_empty() {
+                zigzag_encode(values.len() as i64, writer)?;
+                for (key, value) in values {
+                    write_binary(key.as_bytes(), writer)?;
+                    encode(value, item, names, writer)?;
+                }
+            }
+            zigzag_encode(0, writer)
+        }
+        (Schema::Union(schemas), Value::Union(index, value)) => {
+            let schema = schemas.get(*index).ok_or_else(|| {
+                Error::InvalidValue(format!("the union has no variant with index {}", index))
+            })?;
+            zigzag_encode(*index as i64, writer)?;
+            encode(value, schema, names, writer)
+        }
+        (Schema::Union(schemas), value) => {
+            // the value is written with the first variant of the union that describes it
+            let mut scratch = vec![];
+            for (index, schema) in schemas.iter().enumerate() {
+                scratch.clear();
+                if encode(value, schema, names, &mut scratch).is_ok() {
+                    zigzag_encode(index as i64, writer)?;
+                    writer.write_all(&scratch)?;
+                    return Ok(());
+                }
+            }
+            Err(Error::InvalidValue(format!(
+                "no variant of the union describes a value of type {}",
+                value_type(value)
+            )))
+        }
+        (Schema::Fixed(fixed), Value::Fixed(value)) => {
+            if value.len() != fixed.size {
+                return Err(Error::InvalidValue(format!(
+                    "the fixed \"{}\" has {} bytes but the value has {}",
+                    fixed.name,
+                    fixed.size,
+                    value.len()
+                )));
+            }
+            if let Some(FixedLogical::Decimal(precision, _)) = fixed.logical {
+                check_decimal(value, precision)?;
+            }
+            writer.write_all(value)?;
+            Ok(())
+        }
+        (Schema::Ref(name), value) => {
+            let schema = names.get(name).ok_or_else(|| {
+                Error::InvalidValue(format!("the type \"{}\" is not defined", name))
+            })?;
+            encode(value, schema.borrow(), names, writer)
+        }
+        (Schema::Annotated(schema, _), value) => encode(value, schema, names, writer),
+        (schema, value) => Err(Error::InvalidValue(format!(
+            "a value of type {} cannot be written as {}",
+            value_type(value),
+            schema.type_name()
+        ))),
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Boolean(_) => "boolean",
+        Value::Int(_) => "int",
+        Value::Long(_) => "long",
+        Value::Float(_) => "float",
+        Value::Double(_) => "double",
+        Value::Bytes(_) => "bytes",
+        Value::String(_) => "string",
+        Value::Record(_) => "record",
+        Value::Enum(_) => "enum",
+        Value::Array(_) => "array",
+        Value::Map(_) => "map",
+        Value::Union(_, _) => "union",
+        Value::Fixed(_) => "fixed",
+    }
+}
+
+/// Checks that `value` is in `[0, end)`
+fn check_range(value: i64, end: i64) -> Result<(), Error> {
+    if (0..end).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue(format!(
+            "the time {} is not in [0, {})",
+            value, end
+        )))
+    }
+}
+
+/// Checks that `value` is of the form `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, where `x` is an hexadecimal digit.
+fn check_uuid(value: &str) -> Result<(), Error> {
+    let is_valid = value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue(format!("\"{}\" is not an uuid", value)))
+    }
+}
+
+/// Checks that the two's-complement big-endian integer `value` has at most `precision` digits.
+fn check_decimal(value: &[u8], precision: usize) -> Result<(), Error> {
+    // drop redundant sign-extension bytes
+    let mut value = value;
+    while value.len() > 1
+        && ((value[0] == 0x00 && value[1] & 0x80 == 0)
+            || (value[0] == 0xFF && value[1] & 0x80 != 0))
+    {
+        value = &value[1..];
+    }
+
+    let is_valid = if value.len() <= 16 {
+        let sign = if value.first().map(|x| x & 0x80 != 0).unwrap_or(false) {
+            0xFF
+        } else {
+            0x00
+        };
+        let mut bytes = [sign; 16];
+        bytes[16 - value.len()..].copy_from_slice(value);
+        let value = i128::from_be_bytes(bytes).unsigned_abs();
+        precision > 38 || value < 10u128.pow(precision as u32)
+    } else {
+        // the number of bytes required to represent any integer of `precision` digits
+        let required = ((precision as f64 * 10f64.log2() + 1.0) / 8.0).ceil() as usize;
+        value.len() <= required
+    };
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue(format!(
+            "the decimal does not fit in {} digits",
+            precision
+        )))
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::error::Error;
+use crate::file::{Block, CompressedBlock, FileMetadata};
+use crate::schema::Schema;
+use crate::value::Value;
+
+use super::{compress_with_level, serialize::encode_block, write_block, write_metadata};
+
+/// Options of [`FileWriter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// A block is written once its uncompressed data reaches this number of bytes
+    pub block_size: Option<usize>,
+    /// A block is written once it reaches this number of rows
+    pub block_rows: Option<usize>,
+    /// The compression level of files compressed with Zstandard (0 means zstd's default level)
+    pub zstd_level: i32,
+}
+
+impl Default for WriteOptions {
+    /// Blocks of about 64 KB of uncompressed data, the default of the reference implementation.
+    fn default() -> Self {
+        Self {
+            block_size: Some(64_000),
+            block_rows: None,
+            zstd_level: 0,
+        }
+    }
+}
+
+/// Writes rows of the file's [`Schema`] to an Avro file, grouping them into blocks.
+///
+/// Rows are buffered and written as a (compressed) block whenever the block reaches
+/// one of the thresholds of [`WriteOptions`].
+/// Any remaining rows are written by [`FileWriter::finish`] or when the writer is dropped.
+pub struct FileWriter<W: Write> {
+    // `None` once taken by `finish`
+    writer: Option<W>,
+    metadata: FileMetadata,
+    // copies of the named types of the file's schema, which its references refer to; they
+    // cannot borrow the schema, which `metadata` owns, and are built once instead of per row
+    names: HashMap<String, Schema>,
+    options: WriteOptions,
+    block: Block,
+    compressed: CompressedBlock,
+}
+
+impl<W: Write> FileWriter<W> {
+    /// Returns a new [`FileWriter`], writing the file's metadata to `writer`.
+    pub fn try_new(
+        mut writer: W,
+        metadata: FileMetadata,
+        options: WriteOptions,
+    ) -> Result<Self, Error> {
+        write_metadata(&mut writer, &metadata)?;
+
+        let names = metadata
+            .schema
+            .named_types()
+            .into_iter()
+            .map(|(name, schema)| (name, schema.clone()))
+            .collect();
+
+        Ok(Self {
+            writer: Some(writer),
+            metadata,
+            names,
+            options,
+            block: Block::default(),
+            compressed: CompressedBlock::default(),
+        })
+    }
+
+    /// Appends a row to the file.
+    /// # Error
+    /// This function errors iff `value` is not described by the file's schema or the block
+    /// could not be written.
+    pub fn append(&mut self, value: &Value) -> Result<(), Error> {
+        encode_block(value, &self.metadata.schema, &self.names, &mut self.block)?;
+
+        let is_full = self
+            .options
+            .block_size
+            .map(|size| self.block.data.len() >= size)
+            .unwrap_or(false)
+            || self
+                .options
+                .block_rows
+                .map(|rows| self.block.number_of_rows >= rows)
+                .unwrap_or(false);
+        if is_full {
+            self.write_block()?;
+        }
+        Ok(())
+    }
+
+    /// Compresses and writes the rows appended so far as a block, if any.
+    pub fn write_block(&mut self) -> Result<(), Error> {
+        let writer = match (self.block.number_of_rows, self.writer.as_mut()) {
+            (0, _) | (_, None) => return Ok(()),
+            (_, Some(writer)) => writer,
+        };
+        compress_with_level(
+            &mut self.block,
+            &mut self.compressed,
+            self.metadata.compression,
+            self.options.zstd_level,
+        )?;
+        write_block(writer, &self.compressed, self.metadata.marker)?;
+
+        self.block.number_of_rows = 0;
+        self.block.data.clear();
+        Ok(())
+    }
+
+    /// Writes the remaining rows, flushes the underlying writer and returns it.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let result = self.write_block();
+        // the writer is taken even on error, so that dropping `self` does not write again
+        let mut writer = self
+            .writer
+            .take()
+            .expect("the writer is only taken by finish");
+        result?;
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> Drop for FileWriter<W> {
+    fn drop(&mut self) {
+        // errors can't be reported here; use `finish` to handle them
+        let _ = self.write_block();
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
@@ -3,39 +3,36 @@ use futures::{AsyncWrite, AsyncWriteExt};
 
 use crate::{
     error::Error,
-    file::{CompressedBlock, Compression},
-    schema::{Record, Schema},
+    file::{CompressedBlock, FileMetadata},
     write::encode::zigzag_encode,
-    write::file::{write_schema, AVRO_MAGIC, SYNC_NUMBER},
+    write::file::{write_header, AVRO_MAGIC},
 };
 
 /// Writes Avro's metadata to `writer`.
-pub async fn write_metadata<W>(
-    writer: &mut W,
-    record: Record,
-    compression: Option<Compression>,
-) -> Result<(), Error>
+pub async fn write_metadata<W>(writer: &mut W, metadata: &FileMetadata) -> Result<(), Error>
 where
     W: AsyncWrite + Unpin,
 {
     writer.write_all(&AVRO_MAGIC).await?;
 
     // * file metadata, including the schema.
-    let schema = Schema::Record(record);
-
     let mut scratch = vec![];
-    write_schema(&mut scratch, &schema, compression)?;
+    write_header(&mut scratch, metadata)?;
 
     writer.write_all(&scratch).await?;
 
     // The 16-byte, randomly-generated sync marker for this file.
-    writer.write_all(&SYNC_NUMBER).await?;
+    writer.write_all(&metadata.marker).await?;
 
     Ok(())
 }
 
-/// Writes a [`CompressedBlock`] to `writer`
-pub async fn write_block<W>(writer: &mut W, block: &CompressedBlock) -> Result<(), Error>
+/// Writes a [`CompressedBlock`] to `writer`, followed by the file's `marker`
+pub async fn write_block<W>(
+    writer: &mut W,
+    block: &CompressedBlock,
+    marker: [u8; 16],
+) -> Result<(), Error>
 where
     W: AsyncWrite + Unpin,
 {
@@ -49,7 +46,7 @@ where
 
     writer.write_all(&block.data).await?;
 
-    writer.write_all(&SYNC_NUMBER).await?;
+    writer.write_all(&marker).await?;
 
     Ok(())
 }
@@ -0,0 +1,118 @@
+use avro_schema::schema::Schema;
+
+/// Pairs of (schema, canonical form) from the reference implementation's test suite
+fn cases() -> Vec<(&'static str, &'static str)> {
+    vec![
+        (r#""null""#, r#""null""#),
+        (r#"{"type":"null"}"#, r#""null""#),
+        (r#""boolean""#, r#""boolean""#),
+        (r#"{"type":"boolean"}"#, r#""boolean""#),
+        (r#""int""#, r#""int""#),
+        (r#"{"type":"int"}"#, r#""int""#),
+        (r#""long""#, r#""long""#),
+        (r#"{"type":"long"}"#, r#""long""#),
+        (r#""float""#, r#""float""#),
+        (r#"{"type":"float"}"#, r#""float""#),
+        (r#""double""#, r#""double""#),
+        (r#"{"type":"double"}"#, r#""double""#),
+        (r#""bytes""#, r#""bytes""#),
+        (r#"{"type":"bytes"}"#, r#""bytes""#),
+        (r#""string""#, r#""string""#),
+        (r#"{"type":"string"}"#, r#""string""#),
+        (r#"{"type":"int","logicalType":"date"}"#, r#""int""#),
+        (
+            r#"{"type":"fixed","name":"Test","size":1}"#,
+            r#"{"name":"Test","type":"fixed","size":1}"#,
+        ),
+        (
+            r#"{"type":"fixed","name":"MyFixed","namespace":"org.apache.hadoop.avro","size":1}"#,
+            r#"{"name":"org.apache.hadoop.avro.MyFixed","type":"fixed","size":1}"#,
+        ),
+        (
+            r#"{"type":"enum","name":"Test","symbols":["A","B"]}"#,
+            r#"{"name":"Test","type":"enum","symbols":["A","B"]}"#,
+        ),
+        (
+            r#"{"type":"array","items":"long"}"#,
+            r#"{"type":"array","items":"long"}"#,
+        ),
+        (
+            r#"{"type":"array","items":{"type":"enum","name":"Test","symbols":["A","B"]}}"#,
+            r#"{"type":"array","items":{"name":"Test","type":"enum","symbols":["A","B"]}}"#,
+        ),
+        (
+            r#"{"type":"map","values":"long"}"#,
+            r#"{"type":"map","values":"long"}"#,
+        ),
+        (
+            r#"{"type":"map","values":{"type":"enum","name":"Test","symbols":["A","B"]}}"#,
+            r#"{"type":"map","values":{"name":"Test","type":"enum","symbols":["A","B"]}}"#,
+        ),
+        (r#"["string","null","long"]"#, r#"["string","null","long"]"#),
+        (
+            r#"{"type":"record","name":"Test","fields":[{"name":"f","type":"long"}]}"#,
+            r#"{"name":"Test","type":"record","fields":[{"name":"f","type":"long"}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"Node","fields":[{"name":"label","type":"string"},{"name":"children","type":{"type":"array","items":"Node"}}]}"#,
+            r#"{"name":"Node","type":"record","fields":[{"name":"label","type":"string"},{"name":"children","type":{"type":"array","items":"Node"}}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"Lisp","fields":[{"name":"value","type":["null","string",{"type":"record","name":"Cons","fields":[{"name":"car","type":"Lisp"},{"name":"cdr","type":"Lisp"}]}]}]}"#,
+            r#"{"name":"Lisp","type":"record","fields":[{"name":"value","type":["null","string",{"name":"Cons","type":"record","fields":[{"name":"car","type":"Lisp"},{"name":"cdr","type":"Lisp"}]}]}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"HandshakeRequest","namespace":"org.apache.avro.ipc","fields":[{"name":"clientHash","type":{"type":"fixed","name":"MD5","size":16}},{"name":"clientProtocol","type":["null","string"]},{"name":"serverHash","type":"MD5"},{"name":"meta","type":["null",{"type":"map","values":"bytes"}]}]}"#,
+            r#"{"name":"org.apache.avro.ipc.HandshakeRequest","type":"record","fields":[{"name":"clientHash","type":{"name":"org.apache.avro.ipc.MD5","type":"fixed","size":16}},{"name":"clientProtocol","type":["null","string"]},{"name":"serverHash","type":"org.apache.avro.ipc.MD5"},{"name":"meta","type":["null",{"type":"map","values":"bytes"}]}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"HandshakeResponse","namespace":"org.apache.avro.ipc","fields":[{"name":"match","type":{"type":"enum","name":"HandshakeMatch","symbols":["BOTH","CLIENT","NONE"]}},{"name":"serverProtocol","type":["null","string"]},{"name":"serverHash","type":["null",{"name":"MD5","size":16,"type":"fixed"}]},{"name":"meta","type":["null",{"type":"map","values":"bytes"}]}]}"#,
+            r#"{"name":"org.apache.avro.ipc.HandshakeResponse","type":"record","fields":[{"name":"match","type":{"name":"org.apache.avro.ipc.HandshakeMatch","type":"enum","symbols":["BOTH","CLIENT","NONE"]}},{"name":"serverProtocol","type":["null","string"]},{"name":"serverHash","type":["null",{"name":"org.apache.avro.ipc.MD5","type":"fixed","size":16}]},{"name":"meta","type":["null",{"type":"map","values":"bytes"}]}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"Interop","namespace":"org.apache.avro","fields":[{"name":"intField","type":"int"},{"name":"longField","type":"long"},{"name":"stringField","type":"string"},{"name":"boolField","type":"boolean"},{"name":"floatField","type":"float"},{"name":"doubleField","type":"double"},{"name":"bytesField","type":"bytes"},{"name":"nullField","type":"null"},{"name":"arrayField","type":{"type":"array","items":"double"}},{"name":"mapField","type":{"type":"map","values":{"name":"Foo","type":"record","fields":[{"name":"label","type":"string"}]}}},{"name":"unionField","type":["boolean","double",{"type":"array","items":"bytes"}]},{"name":"enumField","type":{"type":"enum","name":"Kind","symbols":["A","B","C"]}},{"name":"fixedField","type":{"type":"fixed","name":"MD5","size":16}},{"name":"recordField","type":{"type":"record","name":"Node","fields":[{"name":"label","type":"string"},{"name":"children","type":{"type":"array","items":"Node"}}]}}]}"#,
+            r#"{"name":"org.apache.avro.Interop","type":"record","fields":[{"name":"intField","type":"int"},{"name":"longField","type":"long"},{"name":"stringField","type":"string"},{"name":"boolField","type":"boolean"},{"name":"floatField","type":"float"},{"name":"doubleField","type":"double"},{"name":"bytesField","type":"bytes"},{"name":"nullField","type":"null"},{"name":"arrayField","type":{"type":"array","items":"double"}},{"name":"mapField","type":{"type":"map","values":{"name":"org.apache.avro.Foo","type":"record","fields":[{"name":"label","type":"string"}]}}},{"name":"unionField","type":["boolean","double",{"type":"array","items":"bytes"}]},{"name":"enumField","type":{"name":"org.apache.avro.Kind","type":"enum","symbols":["A","B","C"]}},{"name":"fixedField","type":{"name":"org.apache.avro.MD5","type":"fixed","size":16}},{"name":"recordField","type":{"name":"org.apache.avro.Node","type":"record","fields":[{"name":"label","type":"string"},{"name":"children","type":{"type":"array","items":"org.apache.avro.Node"}}]}}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"ipAddr","fields":[{"name":"addr","type":[{"name":"IPv6","type":"fixed","size":16},{"name":"IPv4","type":"fixed","size":4}]}]}"#,
+            r#"{"name":"ipAddr","type":"record","fields":[{"name":"addr","type":[{"name":"IPv6","type":"fixed","size":16},{"name":"IPv4","type":"fixed","size":4}]}]}"#,
+        ),
+        (
+            r#"{"type":"record","name":"TestDoc","doc":"Doc string","fields":[{"name":"name","type":"string","doc":"Doc String"}]}"#,
+            r#"{"name":"TestDoc","type":"record","fields":[{"name":"name","type":"string"}]}"#,
+        ),
+        (
+            r#"{"type":"enum","name":"Test","symbols":["A","B"],"doc":"Doc String"}"#,
+            r#"{"name":"Test","type":"enum","symbols":["A","B"]}"#,
+        ),
+    ]
+}
+
+#[test]
+fn canonical_form() -> serde_json::Result<()> {
+    for (data, expected) in cases() {
+        let schema: Schema = serde_json::from_str(data)?;
+        assert_eq!(schema.canonical_form(), expected, "{}", data);
+    }
+    Ok(())
+}
+
+#[test]
+fn canonical_form_whitespace() -> serde_json::Result<()> {
+    let schema: Schema = serde_json::from_str(
+        r#"{
+            "type": "record",
+            "name": "Test",
+            "namespace": "a.b",
+            "aliases": ["Other"],
+            "fields": [
+                {"name": "f", "type": {"type": "long"}, "order": "descending"}
+            ]
+        }"#,
+    )?;
+    assert_eq!(
+        schema.canonical_form(),
+        r#"{"name":"a.b.Test","type":"record","fields":[{"name":"f","type":"long"}]}"#
+    );
+    Ok(())
+}
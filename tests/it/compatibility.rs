@@ -0,0 +1,115 @@
+use avro_schema::compatibility::{check, Compatibility, VersionIncompatibility};
+use avro_schema::resolve::{incompatibilities, Incompatibility};
+use avro_schema::schema::Schema;
+
+fn parse(schema: &str) -> Schema {
+    serde_json::from_str(schema).unwrap()
+}
+
+fn incompatibility(path: &str, reason: &str) -> Incompatibility {
+    Incompatibility {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn versions() -> Vec<Schema> {
+    vec![
+        parse(
+            r#"{"type": "record", "name": "User", "fields": [
+                {"name": "id", "type": "int"}
+            ]}"#,
+        ),
+        parse(
+            r#"{"type": "record", "name": "User", "fields": [
+                {"name": "id", "type": "int"},
+                {"name": "email", "type": ["null", "string"], "default": null}
+            ]}"#,
+        ),
+    ]
+}
+
+#[test]
+fn compatible() {
+    let schema = parse(
+        r#"{"type": "record", "name": "User", "fields": [
+            {"name": "id", "type": "long"},
+            {"name": "email", "type": ["null", "string"], "default": null},
+            {"name": "nickname", "type": ["null", "string"], "default": null}
+        ]}"#,
+    );
+    assert_eq!(check(&schema, &versions(), Compatibility::Backward), vec![]);
+    // the previous versions cannot read `id` as an int
+    let expected = (0..2)
+        .map(|version| VersionIncompatibility {
+            version,
+            compatibility: Compatibility::Forward,
+            incompatibility: incompatibility("User.id", "type changed from long to int"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        check(&schema, &versions(), Compatibility::Forward),
+        expected
+    );
+    assert_eq!(check(&schema, &versions(), Compatibility::Full), expected);
+}
+
+#[test]
+fn backward_incompatible() {
+    let schema = parse(
+        r#"{"type": "record", "name": "User", "fields": [
+            {"name": "id", "type": "int"},
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "zip", "type": "string"}
+            ]}}
+        ]}"#,
+    );
+    let result = check(&schema, &versions(), Compatibility::Backward);
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[1].version, 1);
+    assert_eq!(
+        result[1].to_string(),
+        "version 1 (backward): User.address: the field is missing from the writer's record and has no default"
+    );
+
+    // the previous versions can read it, ignoring `address`
+    assert_eq!(check(&schema, &versions(), Compatibility::Forward), vec![]);
+}
+
+#[test]
+fn nested_paths() {
+    let previous = parse(
+        r#"{"type": "record", "name": "record", "fields": [
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "zip", "type": "int"},
+                {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}}
+            ]}},
+            {"name": "tags", "type": {"type": "map", "values": ["null", "int"]}}
+        ]}"#,
+    );
+    let schema = parse(
+        r#"{"type": "record", "name": "record", "fields": [
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "zip", "type": "string"},
+                {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A"]}}
+            ]}},
+            {"name": "tags", "type": {"type": "map", "values": "int"}}
+        ]}"#,
+    );
+    let result = incompatibilities(&previous, &schema);
+    assert_eq!(
+        result,
+        vec![
+            incompatibility("record.address.zip", "type changed from int to string"),
+            incompatibility(
+                "record.address.kind",
+                "the symbol \"B\" is missing and the reader has no default"
+            ),
+            incompatibility("record.tags.values[0]", "type changed from null to int"),
+        ]
+    );
+    assert_eq!(
+        check(&schema, &[previous], Compatibility::Backward).len(),
+        result.len()
+    );
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use avro_schema::confluent::{decode, encode, read_header, MAGIC};
+use avro_schema::error::Error;
+use avro_schema::schema::Schema;
+use avro_schema::store::SchemaStore;
+use avro_schema::value::Value;
+
+use super::decode::{expected_row, schema};
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let schema = schema();
+    let mut data = vec![];
+    encode(&expected_row(1), &schema, 258, &mut data)?;
+
+    assert_eq!(data[..5], [MAGIC, 0, 0, 1, 2]);
+    assert_eq!(read_header(&mut &data[..])?, 258);
+
+    let mut store = HashMap::new();
+    store.insert(258u32, schema.clone());
+    store.insert(1, Schema::Null(None));
+
+    let (value, read_schema) = decode(&mut &data[..], &store)?;
+    assert_eq!(value, expected_row(1));
+    assert_eq!(read_schema, &schema);
+    Ok(())
+}
+
+/// A store whose schemas are all the same
+struct Constant(Schema);
+
+impl SchemaStore<u32> for Constant {
+    fn get(&self, _: &u32) -> Option<&Schema> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn custom_store() -> Result<(), Error> {
+    let mut data = vec![];
+    encode(&Value::Long(3), &Schema::Long(None), 7, &mut data)?;
+    assert_eq!(data, [MAGIC, 0, 0, 0, 7, 6]);
+
+    let store = Constant(Schema::Long(None));
+    let (value, _) = decode(&mut &data[..], &store)?;
+    assert_eq!(value, Value::Long(3));
+    Ok(())
+}
+
+#[test]
+fn unknown_schema_id() {
+    let data = [MAGIC, 0, 0, 0, 7, 6];
+    let store: HashMap<u32, Schema> = HashMap::new();
+    let error = decode(&mut &data[..], &store).unwrap_err();
+    assert!(matches!(error, Error::UnknownSchemaId(7)));
+}
+
+#[test]
+fn invalid_magic() {
+    let data = [1, 0, 0, 0, 7, 6];
+    let error = read_header(&mut &data[..]).unwrap_err();
+    assert!(matches!(error, Error::InvalidConfluentMagic(1)));
+
+    let error = read_header(&mut &data[..3]).unwrap_err();
+    assert!(matches!(error, Error::InvalidConfluentMagic(1)));
+
+    let error = read_header(&mut &[MAGIC, 0][..]).unwrap_err();
+    assert!(matches!(error, Error::Io(_)));
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use avro_schema::error::Error;
+use avro_schema::file::Block;
+use avro_schema::read::Decoder;
+use avro_schema::schema::{Enum, Field, Fixed, Record, Schema};
+use avro_schema::value::Value;
+use avro_schema::write::encode::zigzag_encode;
+
+fn encode_string(value: &str, data: &mut Vec<u8>) -> Result<(), Error> {
+    zigzag_encode(value.len() as i64, data)?;
+    data.extend(value.as_bytes());
+    Ok(())
+}
+
+pub fn schema() -> Schema {
+    Schema::Record(Record::new(
+        "Row",
+        vec![
+            Field::new("null", Schema::Null(None)),
+            Field::new("boolean", Schema::Boolean(None)),
+            Field::new("int", Schema::Int(None)),
+            Field::new("long", Schema::Long(None)),
+            Field::new("float", Schema::Float(None)),
+            Field::new("double", Schema::Double(None)),
+            Field::new("bytes", Schema::Bytes(None)),
+            Field::new("string", Schema::String(None)),
+            Field::new(
+                "enum",
+                Enum::new("E", vec!["A".to_string(), "B".to_string()]).into(),
+            ),
+            Field::new("array", Schema::Array(Box::new(Schema::Int(None)))),
+            Field::new("map", Schema::Map(Box::new(Schema::Long(None)))),
+            Field::new(
+                "union",
+                Schema::Union(vec![Schema::Null(None), Schema::String(None)]),
+            ),
+            Field::new("fixed", Fixed::new("F", 2).into()),
+            Field::new("ref", Schema::Ref("F".to_string())),
+        ],
+    ))
+}
+
+fn encode_row(index: i64, data: &mut Vec<u8>) -> Result<(), Error> {
+    // boolean
+    data.push(1);
+    zigzag_encode(-index, data)?;
+    zigzag_encode(i64::MAX - index, data)?;
+    data.extend(1.5f32.to_le_bytes());
+    data.extend((-1.5f64).to_le_bytes());
+    // bytes
+    zigzag_encode(2, data)?;
+    data.extend([0, 255]);
+    encode_string("hello", data)?;
+    // enum
+    zigzag_encode(1, data)?;
+    // array of two blocks: 2 items, and 1 item with a negative count followed by its size
+    zigzag_encode(2, data)?;
+    zigzag_encode(1, data)?;
+    zigzag_encode(2, data)?;
+    zigzag_encode(-1, data)?;
+    zigzag_encode(1, data)?;
+    zigzag_encode(3, data)?;
+    zigzag_encode(0, data)?;
+    // map with a single block
+    zigzag_encode(-1, data)?;
+    zigzag_encode(3, data)?;
+    encode_string("a", data)?;
+    zigzag_encode(index, data)?;
+    zigzag_encode(0, data)?;
+    // union
+    zigzag_encode(1, data)?;
+    encode_string("u", data)?;
+    // fixed and ref
+    data.extend([1, 2, 3, 4]);
+    Ok(())
+}
+
+pub fn expected_row(index: i64) -> Value {
+    Value::Record(vec![
+        ("null".to_string(), Value::Null),
+        ("boolean".to_string(), Value::Boolean(true)),
+        ("int".to_string(), Value::Int(-index as i32)),
+        ("long".to_string(), Value::Long(i64::MAX - index)),
+        ("float".to_string(), Value::Float(1.5)),
+        ("double".to_string(), Value::Double(-1.5)),
+        ("bytes".to_string(), Value::Bytes(vec![0, 255])),
+        ("string".to_string(), Value::String("hello".to_string())),
+        ("enum".to_string(), Value::Enum("B".to_string())),
+        (
+            "array".to_string(),
+            Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+        ),
+        (
+            "map".to_string(),
+            Value::Map(HashMap::from([("a".to_string(), Value::Long(index))])),
+        ),
+        (
+            "union".to_string(),
+            Value::Union(1, Box::new(Value::String("u".to_string()))),
+        ),
+        ("fixed".to_string(), Value::Fixed(vec![1, 2])),
+        ("ref".to_string(), Value::Fixed(vec![3, 4])),
+    ])
+}
+
+#[test]
+fn decode_block() -> Result<(), Error> {
+    let mut data = vec![];
+    encode_row(0, &mut data)?;
+    encode_row(1, &mut data)?;
+    let block = Block::new(2, data);
+
+    let schema = schema();
+    let mut values = vec![];
+    Decoder::new(&schema).decode_block(&block, &mut values)?;
+
+    assert_eq!(values, vec![expected_row(0), expected_row(1)]);
+    Ok(())
+}
+
+#[test]
+fn decode_recursive() -> Result<(), Error> {
+    let schema: Schema = serde_json::from_str(
+        r#"{
+            "type": "record",
+            "name": "Node",
+            "fields": [
+                {"name": "value", "type": "int"},
+                {"name": "next", "type": ["null", "Node"]}
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let data = [2, 2, 4, 0];
+    let value = Decoder::new(&schema).decode(&mut &data[..])?;
+
+    let expected = Value::Record(vec![
+        ("value".to_string(), Value::Int(1)),
+        (
+            "next".to_string(),
+            Value::Union(
+                1,
+                Box::new(Value::Record(vec![
+                    ("value".to_string(), Value::Int(2)),
+                    ("next".to_string(), Value::Union(0, Box::new(Value::Null))),
+                ])),
+            ),
+        ),
+    ]);
+    assert_eq!(value, expected);
+    Ok(())
+}
+
+#[test]
+fn decode_out_of_spec() {
+    let schema = schema();
+    let decoder = Decoder::new(&schema);
+
+    // too few bytes
+    let mut data = vec![];
+    encode_row(0, &mut data).unwrap();
+    data.pop();
+    assert!(decoder
+        .decode_block(&Block::new(1, data), &mut vec![])
+        .is_err());
+
+    // too many bytes
+    let mut data = vec![];
+    encode_row(0, &mut data).unwrap();
+    data.push(0);
+    assert!(decoder
+        .decode_block(&Block::new(1, data), &mut vec![])
+        .is_err());
+
+    // invalid enum index
+    let schema = Enum::new("E", vec!["A".to_string()]).into();
+    assert!(Decoder::new(&schema).decode(&mut &[2u8][..]).is_err());
+
+    // invalid boolean
+    assert!(Decoder::new(&Schema::Boolean(None))
+        .decode(&mut &[2u8][..])
+        .is_err());
+}
+
+#[test]
+fn decode_too_many_empty_items() -> Result<(), Error> {
+    // an array of 2^60 nulls, which are encoded in zero bytes
+    let mut data = vec![];
+    zigzag_encode(1 << 60, &mut data)?;
+    zigzag_encode(0, &mut data)?;
+
+    let schema = Schema::Array(Box::new(Schema::Null(None)));
+    assert!(matches!(
+        Decoder::new(&schema).decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+
+    // so are empty records
+    let schema = Schema::Array(Box::new(Record::new("A", vec![]).into()));
+    assert!(matches!(
+        Decoder::new(&schema).decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+
+    // a few empty items are read
+    let mut data = vec![];
+    zigzag_encode(3, &mut data)?;
+    zigzag_encode(0, &mut data)?;
+    let schema = Schema::Array(Box::new(Schema::Null(None)));
+    assert_eq!(
+        Decoder::new(&schema).decode(&mut &data[..])?,
+        Value::Array(vec![Value::Null; 3])
+    );
+
+    // unless the maximum is lower
+    assert!(matches!(
+        Decoder::new(&schema)
+            .with_max_empty_items(2)
+            .decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn decode_too_deep() -> Result<(), Error> {
+    let schema: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "Node", "fields": [
+            {"name": "next", "type": ["null", "Node"]}
+        ]}"#,
+    )
+    .unwrap();
+    let decoder = Decoder::new(&schema);
+
+    // each node is nested in the union of the node before it
+    let data = vec![2u8; 1_000_000];
+    assert!(matches!(
+        decoder.decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+
+    // only records, arrays and maps count towards the limit, so a list of 128 nodes is read...
+    let mut data = vec![2u8; 127];
+    data.push(0);
+    assert!(decoder.decode(&mut &data[..]).is_ok());
+
+    // ... while a list of 129 nodes is not
+    let mut data = vec![2u8; 128];
+    data.push(0);
+    assert!(matches!(
+        decoder.decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+    Ok(())
+}
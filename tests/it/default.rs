@@ -0,0 +1,143 @@
+use serde_json::json;
+
+use avro_schema::error::Error;
+use avro_schema::resolve::resolve;
+use avro_schema::schema::{Field, Schema};
+use avro_schema::value::Value;
+use avro_schema::write::Encoder;
+
+fn fields(schema: &Schema) -> &[Field] {
+    match schema {
+        Schema::Record(record) => &record.fields,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_and_serialize() -> serde_json::Result<()> {
+    let json = json!({"type": "record", "name": "A", "fields": [
+        {"name": "a", "type": "int", "default": 0},
+        {"name": "b", "type": "string", "default": "abc"},
+        {"name": "c", "type": ["null", "int"], "default": null},
+        {"name": "d", "type": {"type": "map", "values": "long"}, "default": {"a": 1}},
+        {"name": "e", "type": "bytes", "default": "\u{00ff}a"},
+        {"name": "f", "type": {"type": "record", "name": "B", "fields": [
+            {"name": "x", "type": "double"},
+            {"name": "y", "type": "boolean", "default": true}
+        ]}, "default": {"x": 1.5}},
+        {"name": "g", "type": {"type": "enum", "name": "C", "symbols": ["X", "Y"]}, "default": "Y"},
+        {"name": "h", "type": {"type": "array", "items": "C"}, "default": ["X"]}
+    ]});
+    let schema: Schema = serde_json::from_value(json.clone())?;
+
+    let defaults = fields(&schema)
+        .iter()
+        .map(|field| field.default.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        defaults,
+        vec![
+            Some(json!(0)),
+            Some(json!("abc")),
+            Some(json!(null)),
+            Some(json!({"a": 1})),
+            Some(json!("\u{00ff}a")),
+            Some(json!({"x": 1.5})),
+            Some(json!("Y")),
+            Some(json!(["X"])),
+        ]
+    );
+
+    // a field without default is not the same as a field whose default is null
+    let schema: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "A", "fields": [{"name": "a", "type": "null"}]}"#,
+    )?;
+    assert_eq!(fields(&schema)[0].default, None);
+
+    let schema: Schema = serde_json::from_value(json.clone())?;
+    assert_eq!(serde_json::to_value(&schema)?, json);
+    Ok(())
+}
+
+#[test]
+fn invalid() {
+    let cases = [
+        (r#""int""#, r#""a""#),
+        (r#""int""#, "4294967296"),
+        (r#""long""#, "1.5"),
+        (r#""bytes""#, r#""Ā""#),
+        (r#"["null", "int"]"#, "1"),
+        (
+            r#"{"type": "enum", "name": "E", "symbols": ["A"]}"#,
+            r#""B""#,
+        ),
+        (r#"{"type": "fixed", "name": "F", "size": 2}"#, r#""a""#),
+        (
+            r#"{"type": "record", "name": "R", "fields": [{"name": "x", "type": "int"}]}"#,
+            "{}",
+        ),
+    ];
+    for (schema, default) in cases {
+        let json = format!(
+            r#"{{"type": "record", "name": "A", "fields": [{{"name": "a", "type": {}, "default": {}}}]}}"#,
+            schema, default
+        );
+        assert!(serde_json::from_str::<Schema>(&json).is_err(), "{}", json);
+    }
+
+    let error = serde_json::from_str::<Field>(r#"{"name": "a", "type": "int", "default": 0.5}"#)
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("the default of the field \"a\" is invalid"));
+
+    // a default missing its own field, which would otherwise be completed by itself forever
+    let json = r#"{"type":"record","name":"A","fields":[{"name":"a","type":"int"},{"name":"s","type":"A","default":{"a":1}}]}"#;
+    let error = serde_json::from_str::<Schema>(json).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("the field \"s\" is missing from its own default"));
+
+    // while a default can be completed by the defaults of other fields
+    let json = r#"{"type":"record","name":"A","fields":[
+        {"name":"b","type":{"type":"record","name":"B","fields":[{"name":"c","type":"int","default":1}]},"default":{}}
+    ]}"#;
+    assert!(serde_json::from_str::<Schema>(json).is_ok());
+}
+
+#[test]
+fn resolution() -> Result<(), Error> {
+    let writer: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "A", "fields": [{"name": "a", "type": "int"}]}"#,
+    )
+    .unwrap();
+    let reader: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "A", "fields": [
+            {"name": "a", "type": "int"},
+            {"name": "b", "type": "long", "default": 42},
+            {"name": "c", "type": ["string", "null"], "default": "x"},
+            {"name": "d", "type": {"type": "fixed", "name": "F", "size": 1}, "default": "ÿ"}
+        ]}"#,
+    )
+    .unwrap();
+    let mut data = vec![];
+    Encoder::new(&writer).encode(
+        &Value::Record(vec![("a".to_string(), Value::Int(1))]),
+        &mut data,
+    )?;
+
+    let value = resolve(&writer, &reader)?.decode(&mut &data[..])?;
+    assert_eq!(
+        value,
+        Value::Record(vec![
+            ("a".to_string(), Value::Int(1)),
+            ("b".to_string(), Value::Long(42)),
+            (
+                "c".to_string(),
+                Value::Union(0, Box::new(Value::String("x".to_string())))
+            ),
+            ("d".to_string(), Value::Fixed(vec![255])),
+        ])
+    );
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use avro_schema::error::Error;
+use avro_schema::file::Block;
+use avro_schema::read::Decoder;
+use avro_schema::schema::{
+    BytesLogical, Enum, Fixed, FixedLogical, IntLogical, Schema, StringLogical,
+};
+use avro_schema::value::Value;
+use avro_schema::write::Encoder;
+
+use super::decode::{expected_row, schema};
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let schema = schema();
+    let encoder = Encoder::new(&schema);
+
+    let mut block = Block::default();
+    encoder.encode_block(&expected_row(0), &mut block)?;
+    encoder.encode_block(&expected_row(1), &mut block)?;
+    assert_eq!(block.number_of_rows, 2);
+
+    let mut values = vec![];
+    Decoder::new(&schema).decode_block(&block, &mut values)?;
+    assert_eq!(values, vec![expected_row(0), expected_row(1)]);
+    Ok(())
+}
+
+#[test]
+fn record_fields_by_name() -> Result<(), Error> {
+    let schema: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "A", "fields": [{"name": "a", "type": "int"}, {"name": "b", "type": "long"}]}"#,
+    )
+    .unwrap();
+    let value = Value::Record(vec![
+        ("b".to_string(), Value::Long(2)),
+        ("a".to_string(), Value::Int(1)),
+    ]);
+
+    let mut data = vec![];
+    Encoder::new(&schema).encode(&value, &mut data)?;
+    assert_eq!(data, vec![2, 4]);
+
+    // missing field
+    let value = Value::Record(vec![("a".to_string(), Value::Int(1))]);
+    assert!(Encoder::new(&schema).encode(&value, &mut vec![]).is_err());
+    Ok(())
+}
+
+#[test]
+fn union_branch_selection() -> Result<(), Error> {
+    let schema = Schema::Union(vec![
+        Schema::Null(None),
+        Schema::Long(None),
+        Schema::String(None),
+    ]);
+    let encoder = Encoder::new(&schema);
+
+    let mut data = vec![];
+    encoder.encode(&Value::Null, &mut data)?;
+    encoder.encode(&Value::String("a".to_string()), &mut data)?;
+    encoder.encode(&Value::Union(1, Box::new(Value::Long(1))), &mut data)?;
+    assert_eq!(data, vec![0, 4, 2, b'a', 2, 2]);
+
+    assert!(encoder.encode(&Value::Int(1), &mut vec![]).is_err());
+    assert!(encoder
+        .encode(&Value::Union(0, Box::new(Value::Long(1))), &mut vec![])
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn invalid_values() {
+    let cases = vec![
+        (Schema::Int(None), Value::Long(1)),
+        (
+            Enum::new("E", vec!["A".to_string()]).into(),
+            Value::Enum("B".to_string()),
+        ),
+        (Fixed::new("F", 2).into(), Value::Fixed(vec![1])),
+        (Schema::Int(Some(IntLogical::Time)), Value::Int(-1)),
+        (Schema::Int(Some(IntLogical::Time)), Value::Int(86_400_000)),
+        (
+            Schema::String(Some(StringLogical::Uuid)),
+            Value::String("not-an-uuid".to_string()),
+        ),
+        // 1000 has 4 digits
+        (
+            Schema::Bytes(Some(BytesLogical::Decimal(3, 0))),
+            Value::Bytes(1000i32.to_be_bytes().to_vec()),
+        ),
+        (
+            Schema::Bytes(Some(BytesLogical::Decimal(3, 0))),
+            Value::Bytes((-1000i32).to_be_bytes().to_vec()),
+        ),
+        (
+            Schema::Map(Box::new(Schema::Int(None))),
+            Value::Map(HashMap::from([("a".to_string(), Value::Null)])),
+        ),
+    ];
+    for (schema, value) in cases {
+        let mut block = Block::default();
+        assert!(Encoder::new(&schema)
+            .encode_block(&value, &mut block)
+            .is_err());
+        assert_eq!(block, Block::default());
+    }
+}
+
+#[test]
+fn valid_logical_values() -> Result<(), Error> {
+    let mut decimal = Fixed::new("D", 16);
+    decimal.logical = Some(FixedLogical::Decimal(38, 2));
+    let cases = vec![
+        (Schema::Int(Some(IntLogical::Time)), Value::Int(86_399_999)),
+        (
+            Schema::String(Some(StringLogical::Uuid)),
+            Value::String("123e4567-e89b-12d3-a456-426614174000".to_string()),
+        ),
+        (
+            Schema::Bytes(Some(BytesLogical::Decimal(3, 0))),
+            Value::Bytes((-999i32).to_be_bytes().to_vec()),
+        ),
+        (
+            decimal.into(),
+            Value::Fixed((10i128.pow(38) - 1).to_be_bytes().to_vec()),
+        ),
+    ];
+    for (schema, value) in cases {
+        Encoder::new(&schema).encode(&value, &mut vec![])?;
+    }
+    Ok(())
+}
@@ -1,9 +1,12 @@
 use std::convert::TryInto;
 
 use avro_schema::error::Error;
-use avro_schema::file::{Block, Compression};
+use avro_schema::file::{Block, Compression, FileMetadata};
 use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
 use avro_schema::schema::{Field, Record, Schema};
+use avro_schema::value::Value;
+use avro_schema::write::encode::zigzag_encode;
+use avro_schema::write::{FileWriter, WriteOptions};
 
 fn read_avro(mut data: &[u8]) -> Result<Vec<f32>, Error> {
     let metadata = avro_schema::read::read_metadata(&mut data)?;
@@ -16,7 +19,7 @@ fn read_avro(mut data: &[u8]) -> Result<Vec<f32>, Error> {
 
     let mut values = vec![];
     while let Some(block) = blocks.next()? {
-        let _fields = &metadata.record.fields;
+        let _fields = &metadata.record().unwrap().fields;
         let length = block.number_of_rows;
         let mut block: &[u8] = block.data.as_ref();
         // at this point you can deserialize the block based on `_fields` according
@@ -41,9 +44,10 @@ fn write_avro(
 ) -> Result<Vec<u8>, Error> {
     let mut file = vec![];
 
-    let record = Record::new("", vec![Field::new("value", Schema::Float)]);
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
 
-    avro_schema::write::write_metadata(&mut file, record, compression)?;
+    let metadata = FileMetadata::try_new(record, compression)?;
+    avro_schema::write::write_metadata(&mut file, &metadata)?;
 
     // we need to create a `Block`
     let mut data: Vec<u8> = vec![];
@@ -58,7 +62,7 @@ fn write_avro(
     let _ = avro_schema::write::compress(&mut block, &mut compressed_block, compression)?;
 
     // and finally write it to the file
-    avro_schema::write::write_block(&mut file, &compressed_block)?;
+    avro_schema::write::write_block(&mut file, &compressed_block, metadata.marker)?;
 
     Ok(file)
 }
@@ -72,6 +76,7 @@ fn round_trip() -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(feature = "compression")]
 #[test]
 fn round_trip_deflate() -> Result<(), Error> {
     let original = vec![0.1, 0.2];
@@ -81,6 +86,7 @@ fn round_trip_deflate() -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(feature = "compression")]
 #[test]
 fn round_trip_snappy() -> Result<(), Error> {
     let original = vec![0.1, 0.2];
@@ -89,3 +95,466 @@ fn round_trip_snappy() -> Result<(), Error> {
     assert_eq!(read, original);
     Ok(())
 }
+
+#[cfg(feature = "zstd")]
+#[test]
+fn round_trip_zstd() -> Result<(), Error> {
+    let original = vec![0.1, 0.2];
+    let file = write_avro(Some(Compression::Zstandard), &original)?;
+    let read = read_avro(&file)?;
+    assert_eq!(read, original);
+
+    for level in [1, 19] {
+        let options = WriteOptions {
+            zstd_level: level,
+            ..Default::default()
+        };
+        let file = write_rows(3, Some(Compression::Zstandard), options)?;
+        let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+        assert_eq!(metadata.compression, Some(Compression::Zstandard));
+        assert_eq!(read_avro(&file)?, vec![0.0, 1.0, 2.0]);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn round_trip_bzip2() -> Result<(), Error> {
+    let original = vec![0.1, 0.2];
+    let file = write_avro(Some(Compression::Bzip2), &original)?;
+    let read = read_avro(&file)?;
+    assert_eq!(read, original);
+    Ok(())
+}
+
+#[cfg(feature = "xz2")]
+#[test]
+fn round_trip_xz() -> Result<(), Error> {
+    let original = vec![0.1, 0.2];
+    let file = write_avro(Some(Compression::Xz), &original)?;
+    let read = read_avro(&file)?;
+    assert_eq!(read, original);
+    Ok(())
+}
+
+fn write_rows(
+    rows: usize,
+    compression: Option<Compression>,
+    options: WriteOptions,
+) -> Result<Vec<u8>, Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let metadata = FileMetadata::try_new(record, compression)?;
+    let mut writer = FileWriter::try_new(vec![], metadata, options)?;
+    for i in 0..rows {
+        writer.append(&Value::Record(vec![(
+            "value".to_string(),
+            Value::Float(i as f32),
+        )]))?;
+    }
+    let file = writer.finish()?;
+    Ok(file)
+}
+
+/// A file of the longs `0..rows` with `block_rows` rows per block
+pub fn write_longs(
+    rows: i64,
+    block_rows: usize,
+    compression: Option<Compression>,
+) -> Result<Vec<u8>, Error> {
+    let metadata = FileMetadata::try_new(Schema::Long(None), compression)?;
+    let options = WriteOptions {
+        block_rows: Some(block_rows),
+        ..Default::default()
+    };
+    let mut writer = FileWriter::try_new(vec![], metadata, options)?;
+    for i in 0..rows {
+        writer.append(&Value::Long(i))?;
+    }
+    writer.finish()
+}
+
+fn block_rows(mut data: &[u8]) -> Result<Vec<usize>, Error> {
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let mut blocks = avro_schema::read::BlockStreamingIterator::new(
+        &mut data,
+        metadata.compression,
+        metadata.marker,
+    );
+    let mut rows = vec![];
+    while let Some(block) = blocks.next()? {
+        rows.push(block.number_of_rows);
+    }
+    Ok(rows)
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn file_writer() -> Result<(), Error> {
+    let file = write_rows(3, Some(Compression::Deflate), WriteOptions::default())?;
+    assert_eq!(read_avro(&file)?, vec![0.0, 1.0, 2.0]);
+    assert_eq!(block_rows(&file)?, vec![3]);
+    Ok(())
+}
+
+#[test]
+fn file_writer_block_rows() -> Result<(), Error> {
+    let options = WriteOptions {
+        block_rows: Some(2),
+        ..Default::default()
+    };
+    let file = write_rows(5, None, options)?;
+    assert_eq!(read_avro(&file)?, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(block_rows(&file)?, vec![2, 2, 1]);
+    Ok(())
+}
+
+#[test]
+fn file_writer_block_size() -> Result<(), Error> {
+    // every row is 4 bytes
+    let options = WriteOptions {
+        block_size: Some(10),
+        ..Default::default()
+    };
+    let file = write_rows(7, None, options)?;
+    assert_eq!(block_rows(&file)?, vec![3, 3, 1]);
+    Ok(())
+}
+
+#[test]
+fn file_writer_on_drop() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let mut file = vec![];
+    {
+        let metadata = FileMetadata::try_new(record, None)?;
+        let mut writer = FileWriter::try_new(&mut file, metadata, WriteOptions::default())?;
+        writer.append(&Value::Record(vec![(
+            "value".to_string(),
+            Value::Float(1.0),
+        )]))?;
+    }
+    assert_eq!(read_avro(&file)?, vec![1.0]);
+    Ok(())
+}
+
+#[test]
+fn file_writer_named_types() -> Result<(), Error> {
+    use avro_schema::read::Decoder;
+
+    let schema: Schema = serde_json::from_str(
+        r#"{"type": "record", "name": "a.Node", "fields": [
+            {"name": "value", "type": "long"},
+            {"name": "next", "type": ["null", "a.Node"]}
+        ]}"#,
+    )
+    .unwrap();
+    let node = |value: i64, next: Value| {
+        Value::Record(vec![
+            ("value".to_string(), Value::Long(value)),
+            ("next".to_string(), next),
+        ])
+    };
+    let null = Value::Union(0, Box::new(Value::Null));
+    let list = node(1, Value::Union(1, Box::new(node(2, null.clone()))));
+
+    let metadata = FileMetadata::try_new(schema.clone(), None)?;
+    let mut writer = FileWriter::try_new(vec![], metadata, WriteOptions::default())?;
+    writer.append(&list)?;
+    writer.append(&node(3, null))?;
+    // a value not described by the schema is not written
+    assert!(writer
+        .append(&node(4, Value::Union(2, Box::new(Value::Null))))
+        .is_err());
+    let file = writer.finish()?;
+
+    let mut data = &file[..];
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let mut blocks =
+        avro_schema::read::BlockStreamingIterator::new(data, metadata.compression, metadata.marker);
+    let decoder = Decoder::new(&schema);
+    let mut values = vec![];
+    while let Some(block) = blocks.next()? {
+        decoder.decode_block(block, &mut values)?;
+    }
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0], list);
+    Ok(())
+}
+
+#[test]
+fn random_marker() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let a = FileMetadata::try_new(record.clone(), None)?;
+    let b = FileMetadata::try_new(record, None)?;
+    assert_ne!(a.marker, b.marker);
+    Ok(())
+}
+
+#[test]
+fn deterministic_marker() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let metadata = FileMetadata {
+        marker: [7; 16],
+        ..FileMetadata::try_new(record, None)?
+    };
+    let mut writer = FileWriter::try_new(vec![], metadata, WriteOptions::default())?;
+    writer.append(&Value::Record(vec![(
+        "value".to_string(),
+        Value::Float(1.0),
+    )]))?;
+    let file = writer.finish()?;
+
+    // the marker is written after the header and after every block
+    assert_eq!(file[file.len() - 16..], [7; 16]);
+    let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+    assert_eq!(metadata.marker, [7; 16]);
+    assert_eq!(read_avro(&file)?, vec![1.0]);
+    Ok(())
+}
+
+#[test]
+fn user_metadata() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let mut metadata = FileMetadata::try_new(record, Some(Compression::Snappy))?;
+    metadata
+        .metadata
+        .insert("writer.version".to_string(), b"1.2.3".to_vec());
+    metadata
+        .metadata
+        .insert("lineage".to_string(), b"a -> b".to_vec());
+
+    let mut file = vec![];
+    avro_schema::write::write_metadata(&mut file, &metadata)?;
+
+    let read = avro_schema::read::read_metadata(&mut &file[..])?;
+    assert_eq!(
+        read.metadata.get("writer.version"),
+        Some(&b"1.2.3".to_vec())
+    );
+    assert_eq!(read.metadata.get("lineage"), Some(&b"a -> b".to_vec()));
+    assert_eq!(read.metadata.get("avro.codec"), Some(&b"snappy".to_vec()));
+    assert!(read.metadata.contains_key("avro.schema"));
+    assert_eq!(read.metadata.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn rewrite_metadata() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let mut metadata = FileMetadata::try_new(record, Some(Compression::Snappy))?;
+    metadata
+        .metadata
+        .insert("lineage".to_string(), b"a -> b".to_vec());
+
+    let mut file = vec![];
+    avro_schema::write::write_metadata(&mut file, &metadata)?;
+    let read = avro_schema::read::read_metadata(&mut &file[..])?;
+
+    // the metadata that was read, including `avro.schema` and `avro.codec`, can be written back
+    let mut rewritten = vec![];
+    avro_schema::write::write_metadata(&mut rewritten, &read)?;
+    assert_eq!(avro_schema::read::read_metadata(&mut &rewritten[..])?, read);
+    Ok(())
+}
+
+#[test]
+fn reserved_user_metadata() -> Result<(), Error> {
+    let record = Record::new("", vec![Field::new("value", Schema::Float(None))]);
+    let mut metadata = FileMetadata::try_new(record, None)?;
+    metadata
+        .metadata
+        .insert("avro.custom".to_string(), b"".to_vec());
+
+    assert!(avro_schema::write::write_metadata(&mut vec![], &metadata).is_err());
+    Ok(())
+}
+
+/// Returns the header of a file with the `codec`
+fn header_with_codec(codec: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut file = vec![b'O', b'b', b'j', 1u8];
+    zigzag_encode(2, &mut file)?;
+    for (key, value) in [
+        (
+            b"avro.schema".as_ref(),
+            br#"{"type": "record", "name": "", "fields": []}"#.as_ref(),
+        ),
+        (b"avro.codec", codec),
+    ] {
+        zigzag_encode(key.len() as i64, &mut file)?;
+        file.extend(key);
+        zigzag_encode(value.len() as i64, &mut file)?;
+        file.extend(value);
+    }
+    zigzag_encode(0, &mut file)?;
+    file.extend([0; 16]);
+    Ok(file)
+}
+
+#[test]
+fn unknown_codec() -> Result<(), Error> {
+    let file = header_with_codec(b"brotli")?;
+    match avro_schema::read::read_metadata(&mut &file[..]) {
+        Err(Error::UnknownCodec(codec)) => assert_eq!(codec, "brotli"),
+        other => panic!("expected an unknown codec, got {:?}", other),
+    }
+
+    let file = header_with_codec(b"null")?;
+    let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+    assert_eq!(metadata.compression, None);
+    Ok(())
+}
+
+#[test]
+fn error_invalid_magic() {
+    let file = b"Obj2".to_vec();
+    match avro_schema::read::read_metadata(&mut &file[..]) {
+        Err(Error::InvalidMagic(magic)) => assert_eq!(&magic, b"Obj2"),
+        other => panic!("expected an invalid magic, got {:?}", other),
+    }
+}
+
+#[test]
+fn error_invalid_schema() -> Result<(), Error> {
+    let mut file = header_with_codec(b"null")?;
+    // corrupt the schema's JSON
+    let position = file.iter().position(|x| *x == b'{').unwrap();
+    file[position] = b'[';
+    let error = avro_schema::read::read_metadata(&mut &file[..]).unwrap_err();
+    assert!(matches!(error, Error::InvalidSchema(_)));
+    assert!(std::error::Error::source(&error).is_some());
+    Ok(())
+}
+
+#[test]
+fn error_sync_marker_mismatch() -> Result<(), Error> {
+    let options = WriteOptions {
+        block_rows: Some(1),
+        ..Default::default()
+    };
+    let mut file = write_rows(2, None, options)?;
+    let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+    let len = file.len();
+    file[len - 1] ^= 1;
+
+    let error = block_rows(&file).unwrap_err();
+    match error {
+        Error::SyncMarkerMismatch {
+            block,
+            offset,
+            expected,
+            actual,
+        } => {
+            assert_eq!(block, 1);
+            // each block has 1 byte for rows, 1 byte for its size, 4 bytes of data and its marker
+            assert_eq!(offset, 22);
+            assert_eq!(expected, metadata.marker);
+            assert_ne!(actual, metadata.marker);
+        }
+        other => panic!("expected a sync marker mismatch, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn error_corrupt_block() -> Result<(), Error> {
+    let options = WriteOptions {
+        block_rows: Some(1),
+        ..Default::default()
+    };
+    let mut file = write_rows(2, Some(Compression::Snappy), options)?;
+    // corrupt the checksum of the last block, right before its marker
+    let len = file.len();
+    file[len - 17] ^= 1;
+
+    let mut data = &file[..];
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let header = (file.len() - data.len()) as u64;
+    let mut blocks =
+        avro_schema::read::BlockStreamingIterator::new(data, metadata.compression, metadata.marker)
+            .with_position(header);
+    assert!(blocks.next()?.is_some());
+    let error = blocks.next().unwrap_err();
+    assert!(std::error::Error::source(&error).is_some());
+    match error {
+        Error::CorruptBlock {
+            block,
+            offset,
+            error,
+        } => {
+            assert_eq!(block, 1);
+            // the offset is the position of the block in the file
+            let block_len = (len as u64 - header) / 2;
+            assert_eq!(offset, header + block_len);
+            assert!(matches!(*error, Error::ChecksumMismatch { .. }));
+        }
+        other => panic!("expected a corrupt block, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn error_truncated() -> Result<(), Error> {
+    let file = write_rows(2, None, WriteOptions::default())?;
+    // the file ends within the sync marker of its only block
+    match block_rows(&file[..file.len() - 3]).unwrap_err() {
+        Error::OutOfSpec(message) => assert!(message.starts_with("block 0 "), "{}", message),
+        other => panic!("expected a truncated block, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn non_record_schema() -> Result<(), Error> {
+    use avro_schema::read::Decoder;
+
+    let schemas = vec![
+        Schema::String(None),
+        Schema::Array(Box::new(Schema::Long(None))),
+        Schema::Union(vec![Schema::Null(None), Schema::Double(None)]),
+    ];
+    let rows = vec![
+        Value::String("a".to_string()),
+        Value::Array(vec![Value::Long(1), Value::Long(2)]),
+        Value::Union(1, Box::new(Value::Double(1.5))),
+    ];
+    for (schema, row) in schemas.into_iter().zip(rows) {
+        let metadata = FileMetadata::try_new(schema.clone(), None)?;
+        assert_eq!(metadata.record(), None);
+        let mut writer = FileWriter::try_new(vec![], metadata, WriteOptions::default())?;
+        writer.append(&row)?;
+        let file = writer.finish()?;
+
+        let mut data = &file[..];
+        let metadata = avro_schema::read::read_metadata(&mut data)?;
+        assert_eq!(metadata.schema, schema);
+
+        let decoder = Decoder::new(&metadata.schema);
+        let mut blocks = avro_schema::read::BlockStreamingIterator::new(
+            &mut data,
+            metadata.compression,
+            metadata.marker,
+        );
+        let mut values = vec![];
+        while let Some(block) = blocks.next()? {
+            decoder.decode_block(block, &mut values)?;
+        }
+        assert_eq!(values, vec![row]);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn non_record_schema_async() -> Result<(), Error> {
+    futures::executor::block_on(async {
+        let schema = Schema::Union(vec![Schema::Null(None), Schema::String(None)]);
+        let metadata = FileMetadata::try_new(schema.clone(), None)?;
+        let mut file = vec![];
+        avro_schema::write_async::write_metadata(&mut file, &metadata).await?;
+
+        let metadata = avro_schema::read_async::read_metadata(&mut &file[..]).await?;
+        assert_eq!(metadata.schema, schema);
+        assert_eq!(metadata.record(), None);
+        Ok(())
+    })
+}
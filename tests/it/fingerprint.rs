@@ -0,0 +1,81 @@
+use avro_schema::schema::Schema;
+
+/// Pairs of (schema, Rabin fingerprint) from the reference implementation's test suite
+fn cases() -> Vec<(&'static str, i64)> {
+    vec![
+        (r#""null""#, 7195948357588979594),
+        (r#"{"type":"null"}"#, 7195948357588979594),
+        (r#""boolean""#, -6970731678124411036),
+        (r#""int""#, 8247732601305521295),
+        (r#""long""#, -3434872931120570953),
+        (r#""float""#, 5583340709985441680),
+        (r#""double""#, -8181574048448539266),
+        (r#""bytes""#, 5746618253357095269),
+        (r#""string""#, -8142146995180207161),
+        (
+            r#"{"type":"fixed","name":"Test","size":1}"#,
+            6572239772516444520,
+        ),
+        (
+            r#"{"type":"record","name":"Test","fields":[{"name":"f","type":"long"}]}"#,
+            -8189536545172777747,
+        ),
+        (
+            r#"{"type":"enum","name":"Test","symbols":["A","B"],"doc":"Doc String"}"#,
+            1619747627903132163,
+        ),
+        (r#"{"type":"array","items":"long"}"#, 6059251950232166001),
+    ]
+}
+
+#[test]
+fn fingerprint_rabin() -> serde_json::Result<()> {
+    for (data, expected) in cases() {
+        let schema: Schema = serde_json::from_str(data)?;
+        assert_eq!(schema.fingerprint_rabin() as i64, expected, "{}", data);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "fingerprint")]
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+#[cfg(feature = "fingerprint")]
+#[test]
+fn fingerprint_md5() -> serde_json::Result<()> {
+    let schema: Schema = serde_json::from_str(r#""null""#)?;
+    assert_eq!(
+        hex(&schema.fingerprint_md5()),
+        "9b41ef67651c18488a8b08bb67c75699"
+    );
+
+    let schema: Schema = serde_json::from_str(
+        r#"{"type":"record","name":"Test","doc":"a","fields":[{"name":"f","type":"long"}]}"#,
+    )?;
+    assert_eq!(
+        hex(&schema.fingerprint_md5()),
+        "69531a03db788afe353244cd049b1e6d"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "fingerprint")]
+#[test]
+fn fingerprint_sha256() -> serde_json::Result<()> {
+    let schema: Schema = serde_json::from_str(r#""null""#)?;
+    assert_eq!(
+        hex(&schema.fingerprint_sha256()),
+        "f072cbec3bf8841871d4284230c5e983dc211a56837aed862487148f947d1a1f"
+    );
+
+    let schema: Schema = serde_json::from_str(
+        r#"{"type":"record","name":"Test","doc":"a","fields":[{"name":"f","type":"long"}]}"#,
+    )?;
+    assert_eq!(
+        hex(&schema.fingerprint_sha256()),
+        "9670f15a8f96d23e92830d00b8bd57275e02e3e173ffef7c253c170b6beabeb8"
+    );
+    Ok(())
+}
@@ -1,4 +1,19 @@
+mod canonical;
+mod compatibility;
+mod confluent;
+mod decode;
+mod default;
+mod encode;
 mod file;
+mod fingerprint;
+mod parallel;
+mod properties;
+mod recovery;
+mod resolve;
+mod seek;
+mod single_object;
+mod split;
+mod validate;
 
 use serde_json::Result;
 
@@ -7,12 +22,12 @@ use avro_schema::schema::{BytesLogical, Field, LongLogical, Schema};
 fn cases() -> Vec<(&'static str, Schema)> {
     use Schema::*;
     vec![
-        (r#"null"#, Null),
-        (r#"{"type": "null"}"#, Null),
-        (r#"{"type": null}"#, Null),
-        (r#""null""#, Null),
-        (r#""boolean""#, Boolean),
-        (r#"{"type": "boolean"}"#, Boolean),
+        (r#"null"#, Null(None)),
+        (r#"{"type": "null"}"#, Null(None)),
+        (r#"{"type": null}"#, Null(None)),
+        (r#""null""#, Null(None)),
+        (r#""boolean""#, Boolean(None)),
+        (r#"{"type": "boolean"}"#, Boolean(None)),
         (r#""string""#, String(None)),
         (r#"{"type": "string"}"#, String(None)),
         (r#""bytes""#, Bytes(None)),
@@ -29,10 +44,10 @@ fn cases() -> Vec<(&'static str, Schema)> {
             r#"{"type": "long", "logicalType": "timestamp-millis"}"#,
             Long(Some(LongLogical::TimestampMillis)),
         ),
-        (r#""float""#, Float),
-        (r#"{"type": "float"}"#, Float),
-        (r#""double""#, Double),
-        (r#"{"type": "double"}"#, Double),
+        (r#""float""#, Float(None)),
+        (r#"{"type": "float"}"#, Float(None)),
+        (r#""double""#, Double(None)),
+        (r#"{"type": "double"}"#, Double(None)),
         (
             r#"{"type": "enum", "name": "Test", "symbols": ["A", "B"]}"#,
             Enum(avro_schema::schema::Enum::new(
@@ -40,10 +55,13 @@ fn cases() -> Vec<(&'static str, Schema)> {
                 vec!["A".to_string(), "B".to_string()],
             )),
         ),
-        (r#"["null", "string"]"#, Union(vec![Null, String(None)])),
+        (
+            r#"["null", "string"]"#,
+            Union(vec![Null(None), String(None)]),
+        ),
         (
             r#"[{"type": "null"}, {"type": "string"}]"#,
-            Union(vec![Null, String(None)]),
+            Union(vec![Null(None), String(None)]),
         ),
         (
             r#"{"type": "map", "values": "long"}"#,
@@ -121,15 +139,15 @@ fn cases() -> Vec<(&'static str, Schema)> {
                         )
                         .into(),
                     ),
-                    Field::new("serverProtocol", Union(vec![Null, String(None)])),
+                    Field::new("serverProtocol", Union(vec![Null(None), String(None)])),
                     Field::new(
                         "serverHash",
                         Union(vec![
-                            Null,
+                            Null(None),
                             avro_schema::schema::Fixed::new("MD5", 16).into(),
                         ]),
                     ),
-                    Field::new("meta", Union(vec![Null, Map(Box::new(Bytes(None)))])),
+                    Field::new("meta", Union(vec![Null(None), Map(Box::new(Bytes(None)))])),
                     Field::new(
                         "duration",
                         avro_schema::schema::Fixed {
@@ -139,10 +157,66 @@ fn cases() -> Vec<(&'static str, Schema)> {
                             doc: None,
                             aliases: vec![],
                             logical: Some(avro_schema::schema::FixedLogical::Duration),
+                            properties: Default::default(),
                         }
                         .into(),
                     ),
                 ],
+                properties: Default::default(),
+            }),
+        ),
+        (
+            r#"{
+                "type": "record",
+                "name": "Node",
+                "fields": [
+                    {"name": "value", "type": "long"},
+                    {"name": "next", "type": ["null", "Node"]}
+                ]
+            }"#,
+            Record(avro_schema::schema::Record::new(
+                "Node",
+                vec![
+                    Field::new("value", Long(None)),
+                    Field::new("next", Union(vec![Null(None), Ref("Node".to_string())])),
+                ],
+            )),
+        ),
+        (
+            r#"{
+                "type": "record",
+                "name": "Person",
+                "namespace": "org.acme",
+                "fields": [
+                    {
+                        "name": "home",
+                        "type": {
+                            "type": "record",
+                            "name": "Address",
+                            "fields": [{"name": "zip", "type": "int"}]
+                        }
+                    },
+                    {"name": "work", "type": "Address"},
+                    {"name": "other", "type": {"type": "org.acme.Address"}}
+                ]
+            }"#,
+            Record(avro_schema::schema::Record {
+                name: "Person".to_string(),
+                namespace: Some("org.acme".to_string()),
+                doc: None,
+                aliases: vec![],
+                fields: vec![
+                    Field::new(
+                        "home",
+                        Record(avro_schema::schema::Record::new(
+                            "Address",
+                            vec![Field::new("zip", Int(None))],
+                        )),
+                    ),
+                    Field::new("work", Ref("org.acme.Address".to_string())),
+                    Field::new("other", Ref("org.acme.Address".to_string())),
+                ],
+                properties: Default::default(),
             }),
         ),
     ]
@@ -156,3 +230,39 @@ fn test_deserialize() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_round_trip() -> Result<()> {
+    for (_, expected) in cases() {
+        let data = serde_json::to_string(&expected)?;
+        let v: avro_schema::schema::Schema = serde_json::from_str(&data)?;
+        assert_eq!(v, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_names() {
+    let cases = [
+        // undefined
+        r#"{"type": "record", "name": "A", "fields": [{"name": "a", "type": "B"}]}"#,
+        // used before being defined
+        r#"["A", {"type": "fixed", "name": "A", "size": 1}]"#,
+        // declared in a different namespace
+        r#"{
+            "type": "record",
+            "name": "A",
+            "fields": [
+                {"name": "a", "type": {"type": "fixed", "name": "B", "namespace": "x", "size": 1}},
+                {"name": "b", "type": "B"}
+            ]
+        }"#,
+        // defined twice
+        r#"[{"type": "fixed", "name": "A", "size": 1}, {"type": "enum", "name": "A", "symbols": []}]"#,
+        // redefines a primitive
+        r#"{"type": "fixed", "name": "int", "size": 1}"#,
+    ];
+    for data in cases {
+        assert!(serde_json::from_str::<avro_schema::schema::Schema>(data).is_err());
+    }
+}
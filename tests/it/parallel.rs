@@ -0,0 +1,120 @@
+use avro_schema::error::Error;
+use avro_schema::file::Compression;
+use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
+use avro_schema::read::{Decoder, ParallelBlockStreamingIterator};
+use avro_schema::schema::Schema;
+use avro_schema::value::Value;
+
+use super::file::write_longs;
+
+fn blocks(file: Vec<u8>, workers: usize) -> Result<ParallelBlockStreamingIterator, Error> {
+    let mut reader = std::io::Cursor::new(file);
+    let metadata = avro_schema::read::read_metadata(&mut reader)?;
+    Ok(ParallelBlockStreamingIterator::new(
+        reader,
+        metadata.compression,
+        metadata.marker,
+        workers,
+    ))
+}
+
+fn read_parallel(file: Vec<u8>, workers: usize) -> Result<Vec<Value>, Error> {
+    let mut blocks = blocks(file, workers)?;
+    let schema = Schema::Long(None);
+    let decoder = Decoder::new(&schema);
+    let mut values = vec![];
+    while let Some(block) = blocks.next()? {
+        decoder.decode_block(block, &mut values)?;
+    }
+    Ok(values)
+}
+
+fn test(compression: Option<Compression>) -> Result<(), Error> {
+    let expected = (0..1005).map(Value::Long).collect::<Vec<_>>();
+    let file = write_longs(1005, 10, compression)?;
+    for workers in [0, 1, 3, 8] {
+        assert_eq!(read_parallel(file.clone(), workers)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn uncompressed() -> Result<(), Error> {
+    test(None)
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn deflate() -> Result<(), Error> {
+    test(Some(Compression::Deflate))
+}
+
+#[test]
+fn empty() -> Result<(), Error> {
+    let file = write_longs(0, 10, None)?;
+    assert_eq!(read_parallel(file, 2)?, vec![]);
+    Ok(())
+}
+
+#[test]
+fn error() -> Result<(), Error> {
+    let mut file = write_longs(100, 10, None)?;
+    // corrupt the marker of the last block
+    let len = file.len();
+    file[len - 1] ^= 1;
+
+    let mut blocks = blocks(file, 3)?;
+    for _ in 0..9 {
+        assert!(blocks.next()?.is_some());
+    }
+    assert!(matches!(
+        blocks.next(),
+        Err(Error::SyncMarkerMismatch { block: 9, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn error_position() -> Result<(), Error> {
+    let mut file = write_longs(100, 10, None)?;
+    let len = file.len();
+    file[len - 1] ^= 1;
+
+    let mut data = &file[..];
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let header = (file.len() - data.len()) as u64;
+
+    // the offsets are positions in the file, as those of the other readers
+    let mut reader = std::io::Cursor::new(file.clone());
+    reader.set_position(header);
+    let mut blocks =
+        ParallelBlockStreamingIterator::new(reader, metadata.compression, metadata.marker, 3)
+            .with_position(header);
+    let mut expected =
+        avro_schema::read::BlockStreamingIterator::new(data, metadata.compression, metadata.marker)
+            .with_position(header);
+    for _ in 0..9 {
+        assert!(blocks.next()?.is_some());
+        assert!(expected.next()?.is_some());
+    }
+    match (blocks.next(), expected.next()) {
+        (
+            Err(Error::SyncMarkerMismatch { offset, .. }),
+            Err(Error::SyncMarkerMismatch {
+                offset: expected, ..
+            }),
+        ) => assert_eq!(offset, expected),
+        other => panic!("expected sync marker mismatches, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn drop_early() -> Result<(), Error> {
+    let file = write_longs(1000, 10, None)?;
+    let mut blocks = blocks(file, 2)?;
+    assert!(blocks.next()?.is_some());
+    // dropping stops the threads
+    drop(blocks);
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use serde_json::json;
+
+use avro_schema::error::Error;
+use avro_schema::read::Decoder;
+use avro_schema::resolve::resolve;
+use avro_schema::schema::Schema;
+use avro_schema::value::Value;
+use avro_schema::write::Encoder;
+
+fn schema() -> serde_json::Value {
+    json!({"type": "record", "name": "A", "connect.name": "a.A", "fields": [
+        {"name": "a", "type": {"type": "string", "avro.java.string": "String"}, "pii": true},
+        {"name": "b", "type": {"type": "array", "items": "long", "java-class": "java.util.List"}},
+        {"name": "c", "type": {"type": "enum", "name": "C", "symbols": ["X"], "meta": {"v": 1}}},
+        {"name": "d", "type": {"type": "fixed", "name": "D", "size": 2, "tags": ["x", "y"]}}
+    ]})
+}
+
+#[test]
+fn parse_and_serialize() -> serde_json::Result<()> {
+    let json = schema();
+    let schema: Schema = serde_json::from_value(json.clone())?;
+
+    let record = match &schema {
+        Schema::Record(record) => record,
+        _ => unreachable!(),
+    };
+    assert_eq!(record.properties.get("connect.name"), Some(&json!("a.A")));
+    assert_eq!(record.fields[0].properties.get("pii"), Some(&json!(true)));
+    match &record.fields[0].schema {
+        Schema::Annotated(schema, properties) => {
+            assert_eq!(schema.as_ref(), &Schema::String(None));
+            assert_eq!(properties.get("avro.java.string"), Some(&json!("String")));
+        }
+        _ => unreachable!(),
+    }
+    match &record.fields[2].schema {
+        Schema::Enum(enum_) => assert_eq!(enum_.properties.get("meta"), Some(&json!({"v": 1}))),
+        _ => unreachable!(),
+    }
+
+    assert_eq!(serde_json::to_value(&schema)?, json);
+    Ok(())
+}
+
+#[test]
+fn canonical_form_ignores_properties() -> serde_json::Result<()> {
+    let schema: Schema = serde_json::from_value(schema())?;
+    assert_eq!(
+        schema.canonical_form(),
+        r#"{"name":"A","type":"record","fields":[{"name":"a","type":"string"},{"name":"b","type":{"type":"array","items":"long"}},{"name":"c","type":{"name":"C","type":"enum","symbols":["X"]}},{"name":"d","type":{"name":"D","type":"fixed","size":2}}]}"#
+    );
+    Ok(())
+}
+
+#[test]
+fn encode_decode_and_resolve() -> Result<(), Error> {
+    let writer: Schema = serde_json::from_value(schema()).map_err(Error::InvalidSchema)?;
+    let value = Value::Record(vec![
+        ("a".to_string(), Value::String("a".to_string())),
+        ("b".to_string(), Value::Array(vec![Value::Long(1)])),
+        ("c".to_string(), Value::Enum("X".to_string())),
+        ("d".to_string(), Value::Fixed(vec![1, 2])),
+    ]);
+
+    let mut data = vec![];
+    Encoder::new(&writer).encode(&value, &mut data)?;
+    assert_eq!(Decoder::new(&writer).decode(&mut &data[..])?, value);
+
+    // the reader reads the annotated string without its annotation
+    let reader: Schema = serde_json::from_value(json!({"type": "record", "name": "A", "fields": [
+        {"name": "a", "type": "string"}
+    ]}))
+    .map_err(Error::InvalidSchema)?;
+    let resolution = resolve(&writer, &reader)?;
+    assert_eq!(
+        resolution.decode(&mut &data[..])?,
+        Value::Record(vec![("a".to_string(), Value::String("a".to_string()))])
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_logical_types() -> serde_json::Result<()> {
+    use avro_schema::schema::{
+        BooleanLogical, BytesLogical, DoubleLogical, FixedLogical, FloatLogical, IntLogical,
+        NullLogical, StringLogical,
+    };
+
+    let cases = [
+        (
+            json!({"type": "string", "logicalType": "varchar", "maxLength": 10}),
+            Schema::String(Some(StringLogical::Other("varchar".to_string()))),
+        ),
+        (
+            json!({"type": "bytes", "logicalType": "big-decimal"}),
+            Schema::Bytes(Some(BytesLogical::Other("big-decimal".to_string()))),
+        ),
+        (
+            // a decimal without precision is not a valid decimal
+            json!({"type": "bytes", "logicalType": "decimal", "scale": 2}),
+            Schema::Bytes(Some(BytesLogical::Other("decimal".to_string()))),
+        ),
+        (
+            json!({"type": "int", "logicalType": "time-seconds"}),
+            Schema::Int(Some(IntLogical::Other("time-seconds".to_string()))),
+        ),
+        (
+            json!({"type": "null", "logicalType": "nothing"}),
+            Schema::Null(Some(NullLogical::Other("nothing".to_string()))),
+        ),
+        (
+            json!({"type": "boolean", "logicalType": "flag", "default-on": true}),
+            Schema::Boolean(Some(BooleanLogical::Other("flag".to_string()))),
+        ),
+        (
+            json!({"type": "float", "logicalType": "percent"}),
+            Schema::Float(Some(FloatLogical::Other("percent".to_string()))),
+        ),
+        (
+            json!({"type": "double", "logicalType": "celsius"}),
+            Schema::Double(Some(DoubleLogical::Other("celsius".to_string()))),
+        ),
+    ];
+    for (json, expected) in cases {
+        let schema: Schema = serde_json::from_value(json.clone())?;
+        let inner = match &schema {
+            Schema::Annotated(schema, _) => schema.as_ref(),
+            schema => schema,
+        };
+        assert_eq!(inner, &expected);
+        assert_eq!(serde_json::to_value(&schema)?, json);
+    }
+
+    let json =
+        json!({"type": "fixed", "name": "A", "size": 8, "logicalType": "decimal", "precision": -1});
+    let schema: Schema = serde_json::from_value(json.clone())?;
+    match &schema {
+        Schema::Fixed(fixed) => {
+            assert_eq!(
+                fixed.logical,
+                Some(FixedLogical::Other("decimal".to_string()))
+            );
+            assert_eq!(fixed.properties.get("precision"), Some(&json!(-1)));
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(serde_json::to_value(&schema)?, json);
+    Ok(())
+}
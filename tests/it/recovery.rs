@@ -0,0 +1,227 @@
+use std::io::Cursor;
+
+use avro_schema::error::Error;
+use avro_schema::file::{Block, Compression};
+use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
+use avro_schema::read::{
+    BlockStreamingIterator, Decoder, ParallelBlockStreamingIterator,
+    SeekableBlockStreamingIterator, SkippedRange,
+};
+use avro_schema::schema::Schema;
+use avro_schema::value::Value;
+
+use super::file::write_longs;
+
+/// A file of 6 longs with 2 rows per block and the positions where each block starts
+fn write_file(compression: Option<Compression>) -> Result<(Vec<u8>, Vec<usize>), Error> {
+    let file = write_longs(6, 2, compression)?;
+    let marker = avro_schema::read::read_metadata(&mut &file[..])?.marker;
+
+    // every block starts after a marker
+    let starts = file
+        .windows(16)
+        .enumerate()
+        .filter(|(_, x)| *x == marker)
+        .map(|(i, _)| i + 16)
+        .take(3)
+        .collect();
+    Ok((file, starts))
+}
+
+/// Reads the values of `file` in recovery mode, returning them and the skipped ranges
+fn read_recovering(file: &[u8]) -> Result<(Vec<i64>, Vec<SkippedRange>), Error> {
+    let mut data = file;
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let header = (file.len() - data.len()) as u64;
+
+    let mut blocks = BlockStreamingIterator::new(data, metadata.compression, metadata.marker)
+        .with_position(header)
+        .with_recovery();
+
+    let values = read_values(&mut blocks, &metadata.schema)?;
+    Ok((values, blocks.take_skipped()))
+}
+
+/// Reads the values of `file` without recovery
+fn read(file: &[u8]) -> Result<Vec<i64>, Error> {
+    let mut data = file;
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let mut blocks = BlockStreamingIterator::new(data, metadata.compression, metadata.marker);
+    read_values(&mut blocks, &metadata.schema)
+}
+
+fn read_values<I>(blocks: &mut I, schema: &Schema) -> Result<Vec<i64>, Error>
+where
+    I: FallibleStreamingIterator<Item = Block, Error = Error>,
+{
+    let decoder = Decoder::new(schema);
+    let mut values = vec![];
+    while let Some(block) = blocks.next()? {
+        decoder.decode_block(block, &mut values)?;
+    }
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            Value::Long(value) => value,
+            _ => unreachable!(),
+        })
+        .collect())
+}
+
+#[test]
+fn corrupt_size() -> Result<(), Error> {
+    let (mut file, starts) = write_file(None)?;
+    // the second block declares 63 bytes, more than what remains in the file
+    file[starts[1] + 1] = 0x7E;
+
+    let (values, skipped) = read_recovering(&file)?;
+    assert_eq!(values, vec![0, 1, 4, 5]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].offset, starts[1] as u64);
+    // the next marker is found within the bytes read for the corrupt block
+    assert_eq!(skipped[0].len, (starts[2] - starts[1]) as u64);
+    assert_eq!(skipped[0].rows, Some(2));
+    assert!(matches!(skipped[0].error, Error::OutOfSpec(_)));
+    Ok(())
+}
+
+#[test]
+fn empty_block() -> Result<(), Error> {
+    let (mut file, starts) = write_file(None)?;
+    let marker = avro_schema::read::read_metadata(&mut &file[..])?.marker;
+    // a valid block without rows nor data before the second block
+    let empty = [&[0u8, 0][..], &marker[..]].concat();
+    file.splice(starts[1]..starts[1], empty);
+
+    assert_eq!(read(&file)?, vec![0, 1, 2, 3, 4, 5]);
+    let (values, skipped) = read_recovering(&file)?;
+    assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+    assert!(skipped.is_empty());
+
+    // the index lists every block, including the one without rows
+    let mut reader = Cursor::new(&file[..]);
+    let metadata = avro_schema::read::read_metadata(&mut reader)?;
+    let mut blocks = SeekableBlockStreamingIterator::try_new(reader, None, metadata.marker)?;
+    let rows = blocks
+        .index()?
+        .iter()
+        .map(|x| x.number_of_rows)
+        .collect::<Vec<_>>();
+    assert_eq!(rows, vec![2, 0, 2, 2]);
+    Ok(())
+}
+
+#[test]
+fn corrupt_rows() -> Result<(), Error> {
+    let (mut file, starts) = write_file(None)?;
+    // the second block declares no rows but has data
+    file[starts[1]] = 0x00;
+
+    let (values, skipped) = read_recovering(&file)?;
+    assert_eq!(values, vec![0, 1, 4, 5]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].offset, starts[1] as u64);
+    assert_eq!(skipped[0].len, (starts[2] - starts[1]) as u64);
+    assert_eq!(skipped[0].rows, Some(0));
+    assert!(matches!(
+        skipped[0].error,
+        Error::CorruptBlock { block: 1, .. }
+    ));
+
+    // without recovery, the error is returned
+    match read(&file).unwrap_err() {
+        Error::CorruptBlock {
+            block: 1, error, ..
+        } => {
+            assert!(matches!(*error, Error::OutOfSpec(_)))
+        }
+        other => panic!("expected a corrupt block, got {:?}", other),
+    }
+
+    // so does the parallel reader
+    let mut reader = Cursor::new(file);
+    let metadata = avro_schema::read::read_metadata(&mut reader)?;
+    let mut blocks =
+        ParallelBlockStreamingIterator::new(reader, metadata.compression, metadata.marker, 2);
+    assert!(blocks.next()?.is_some());
+    assert!(matches!(
+        blocks.next(),
+        Err(Error::CorruptBlock { block: 1, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn corrupt_marker() -> Result<(), Error> {
+    let (mut file, starts) = write_file(None)?;
+    file[starts[2] - 1] ^= 1;
+
+    let (values, skipped) = read_recovering(&file)?;
+    // the block after the corrupt marker can't be located and is skipped too
+    assert_eq!(values, vec![0, 1]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].offset, starts[1] as u64);
+    assert_eq!(skipped[0].len, (file.len() - starts[1]) as u64);
+    assert!(matches!(
+        skipped[0].error,
+        Error::SyncMarkerMismatch { block: 1, .. }
+    ));
+
+    // without recovery, the error is returned
+    let mut data = &file[..];
+    let metadata = avro_schema::read::read_metadata(&mut data)?;
+    let mut blocks = BlockStreamingIterator::new(data, metadata.compression, metadata.marker);
+    assert!(blocks.next()?.is_some());
+    assert!(matches!(
+        blocks.next(),
+        Err(Error::SyncMarkerMismatch { block: 1, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn truncated() -> Result<(), Error> {
+    let (file, starts) = write_file(None)?;
+    let file = &file[..file.len() - 3];
+
+    let (values, skipped) = read_recovering(file)?;
+    assert_eq!(values, vec![0, 1, 2, 3]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].offset, starts[2] as u64);
+    assert_eq!(skipped[0].len, (file.len() - starts[2]) as u64);
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn corrupt_data() -> Result<(), Error> {
+    let (mut file, starts) = write_file(Some(Compression::Snappy))?;
+    // corrupt the checksum of the second block
+    file[starts[2] - 17] ^= 1;
+
+    let (values, skipped) = read_recovering(&file)?;
+    assert_eq!(values, vec![0, 1, 4, 5]);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].offset, starts[1] as u64);
+    assert_eq!(skipped[0].len, (starts[2] - starts[1]) as u64);
+    assert_eq!(skipped[0].rows, Some(2));
+    match &skipped[0].error {
+        Error::CorruptBlock { block, error, .. } => {
+            assert_eq!(*block, 1);
+            assert!(matches!(error.as_ref(), Error::ChecksumMismatch { .. }));
+        }
+        other => panic!("expected a corrupt block, got {:?}", other),
+    }
+
+    // so does the parallel reader
+    let mut reader = Cursor::new(file);
+    let metadata = avro_schema::read::read_metadata(&mut reader)?;
+    let mut blocks =
+        ParallelBlockStreamingIterator::new(reader, metadata.compression, metadata.marker, 2);
+    assert!(blocks.next()?.is_some());
+    assert!(matches!(
+        blocks.next(),
+        Err(Error::CorruptBlock { block: 1, .. })
+    ));
+    Ok(())
+}
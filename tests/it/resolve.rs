@@ -0,0 +1,320 @@
+use avro_schema::error::Error;
+use avro_schema::resolve::{resolve, Incompatibility};
+use avro_schema::schema::Schema;
+use avro_schema::value::Value;
+use avro_schema::write::Encoder;
+
+fn parse(schema: &str) -> Schema {
+    serde_json::from_str(schema).unwrap()
+}
+
+fn encode(value: &Value, schema: &Schema) -> Result<Vec<u8>, Error> {
+    let mut data = vec![];
+    Encoder::new(schema).encode(value, &mut data)?;
+    Ok(data)
+}
+
+fn record(fields: Vec<(&str, Value)>) -> Value {
+    Value::Record(
+        fields
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+#[test]
+fn record_evolution() -> Result<(), Error> {
+    let writer = parse(
+        r#"{"type": "record", "name": "a.User", "fields": [
+            {"name": "id", "type": "int"},
+            {"name": "removed", "type": {"type": "array", "items": "string"}},
+            {"name": "score", "type": "float"},
+            {"name": "name", "type": "string"},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B", "C"]}}
+        ]}"#,
+    );
+    let reader = parse(
+        r#"{"type": "record", "name": "b.User", "fields": [
+            {"name": "name", "type": "bytes"},
+            {"name": "identifier", "aliases": ["id"], "type": "long"},
+            {"name": "added", "type": ["null", "string"], "default": null},
+            {"name": "score", "type": "double"},
+            {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "Z"], "default": "Z"}}
+        ]}"#,
+    );
+    let resolution = resolve(&writer, &reader)?;
+
+    let data = encode(
+        &record(vec![
+            ("id", Value::Int(1)),
+            (
+                "removed",
+                Value::Array(vec![Value::String("a".to_string())]),
+            ),
+            ("score", Value::Float(1.5)),
+            ("name", Value::String("b".to_string())),
+            ("kind", Value::Enum("C".to_string())),
+        ]),
+        &writer,
+    )?;
+    let value = resolution.decode(&mut &data[..])?;
+    assert_eq!(
+        value,
+        record(vec![
+            ("name", Value::Bytes(b"b".to_vec())),
+            ("identifier", Value::Long(1)),
+            ("added", Value::Union(0, Box::new(Value::Null))),
+            ("score", Value::Double(1.5)),
+            ("kind", Value::Enum("Z".to_string())),
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+fn promotions() -> Result<(), Error> {
+    let cases = vec![
+        ("int", Value::Int(-3), "long", Value::Long(-3)),
+        ("int", Value::Int(-3), "float", Value::Float(-3.0)),
+        ("int", Value::Int(-3), "double", Value::Double(-3.0)),
+        ("long", Value::Long(4), "float", Value::Float(4.0)),
+        ("long", Value::Long(4), "double", Value::Double(4.0)),
+        ("float", Value::Float(0.5), "double", Value::Double(0.5)),
+        (
+            "string",
+            Value::String("a".to_string()),
+            "bytes",
+            Value::Bytes(b"a".to_vec()),
+        ),
+        (
+            "bytes",
+            Value::Bytes(b"a".to_vec()),
+            "string",
+            Value::String("a".to_string()),
+        ),
+    ];
+    for (writer, value, reader, expected) in cases {
+        let writer = parse(&format!("\"{}\"", writer));
+        let reader = parse(&format!("\"{}\"", reader));
+        let data = encode(&value, &writer)?;
+        assert_eq!(resolve(&writer, &reader)?.decode(&mut &data[..])?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn unions() -> Result<(), Error> {
+    // a non-union is read as the first matching variant of the reader's union
+    let writer = parse(r#""int""#);
+    let reader = parse(r#"["null", "double", "long"]"#);
+    let data = encode(&Value::Int(2), &writer)?;
+    assert_eq!(
+        resolve(&writer, &reader)?.decode(&mut &data[..])?,
+        Value::Union(1, Box::new(Value::Double(2.0)))
+    );
+
+    // variants of the same type are preferred to promotions
+    let reader = parse(r#"["null", "double", "int"]"#);
+    assert_eq!(
+        resolve(&writer, &reader)?.decode(&mut &data[..])?,
+        Value::Union(2, Box::new(Value::Int(2)))
+    );
+
+    // a union is read as the reader's type
+    let writer = parse(r#"["null", "int"]"#);
+    let reader = parse(r#""long""#);
+    let resolution = resolve(&writer, &reader)?;
+    let data = encode(&Value::Union(1, Box::new(Value::Int(2))), &writer)?;
+    assert_eq!(resolution.decode(&mut &data[..])?, Value::Long(2));
+
+    // variants that the reader cannot read only error when read
+    let data = encode(&Value::Union(0, Box::new(Value::Null)), &writer)?;
+    let error = resolution.decode(&mut &data[..]).unwrap_err();
+    assert!(
+        matches!(error, Error::IncompatibleSchemas(x) if x == vec![Incompatibility {
+            path: "union[0]".to_string(),
+            reason: "type changed from null to long".to_string(),
+        }])
+    );
+    Ok(())
+}
+
+#[test]
+fn enum_without_default() -> Result<(), Error> {
+    let writer = parse(r#"{"type": "enum", "name": "Kind", "symbols": ["A", "B"]}"#);
+    let reader = parse(r#"{"type": "enum", "name": "Kind", "symbols": ["B"]}"#);
+    let resolution = resolve(&writer, &reader)?;
+
+    let data = encode(&Value::Enum("B".to_string()), &writer)?;
+    assert_eq!(
+        resolution.decode(&mut &data[..])?,
+        Value::Enum("B".to_string())
+    );
+
+    let data = encode(&Value::Enum("A".to_string()), &writer)?;
+    assert!(matches!(
+        resolution.decode(&mut &data[..]),
+        Err(Error::IncompatibleSchemas(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn recursive() -> Result<(), Error> {
+    let writer = parse(
+        r#"{"type": "record", "name": "Node", "fields": [
+            {"name": "value", "type": "int"},
+            {"name": "next", "type": ["null", "Node"]}
+        ]}"#,
+    );
+    let reader = parse(
+        r#"{"type": "record", "name": "Node", "fields": [
+            {"name": "next", "type": ["null", "Node"]},
+            {"name": "value", "type": "long"}
+        ]}"#,
+    );
+    let resolution = resolve(&writer, &reader)?;
+
+    let value = record(vec![
+        ("value", Value::Int(1)),
+        (
+            "next",
+            Value::Union(
+                1,
+                Box::new(record(vec![
+                    ("value", Value::Int(2)),
+                    ("next", Value::Union(0, Box::new(Value::Null))),
+                ])),
+            ),
+        ),
+    ]);
+    let data = encode(&value, &writer)?;
+    let expected = record(vec![
+        (
+            "next",
+            Value::Union(
+                1,
+                Box::new(record(vec![
+                    ("next", Value::Union(0, Box::new(Value::Null))),
+                    ("value", Value::Long(2)),
+                ])),
+            ),
+        ),
+        ("value", Value::Long(1)),
+    ]);
+    assert_eq!(resolution.decode(&mut &data[..])?, expected);
+
+    // data nested without bound errors instead of overflowing the stack
+    let data = [2u8, 2].repeat(1_000_000);
+    assert!(matches!(
+        resolution.decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+
+    // only records, arrays and maps count towards the limit, so a list of 128 nodes is read...
+    let mut data = [2u8, 2].repeat(127);
+    data.extend([2, 0]);
+    assert!(resolution.decode(&mut &data[..]).is_ok());
+
+    // ... while a list of 129 nodes is not
+    let mut data = [2u8, 2].repeat(128);
+    data.extend([2, 0]);
+    assert!(matches!(
+        resolution.decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn too_many_empty_items() -> Result<(), Error> {
+    let schema = parse(r#"{"type": "array", "items": "null"}"#);
+    let value = Value::Array(vec![Value::Null; 3]);
+    let data = encode(&value, &schema)?;
+
+    let resolution = resolve(&schema, &schema)?;
+    assert_eq!(resolution.clone().decode(&mut &data[..])?, value);
+    assert!(matches!(
+        resolution.with_max_empty_items(2).decode(&mut &data[..]),
+        Err(Error::OutOfSpec(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn incompatible_named_type_is_not_reused() -> Result<(), Error> {
+    let schema = |x: &str| {
+        parse(&format!(
+            r#"{{"type": "record", "name": "Root", "fields": [
+                {{"name": "u", "type": ["null", {{"type": "record", "name": "A", "fields": [
+                    {{"name": "x", "type": "{}"}},
+                    {{"name": "b", "type": {{"type": "record", "name": "B", "fields": [
+                        {{"name": "a", "type": ["null", "A"]}}
+                    ]}}}}
+                ]}}]}},
+                {{"name": "v", "type": ["null", "B"]}}
+            ]}}"#,
+            x
+        ))
+    };
+    let (writer, reader) = (schema("int"), schema("boolean"));
+    // the writer's `A` cannot be read, which only errors when data of it is read
+    let resolution = resolve(&writer, &reader)?;
+
+    let b = |a: Value| record(vec![("a", a)]);
+    let a = record(vec![
+        ("x", Value::Int(1)),
+        ("b", b(Value::Union(0, Box::new(Value::Null)))),
+    ]);
+    let value = record(vec![
+        ("u", Value::Union(0, Box::new(Value::Null))),
+        (
+            "v",
+            Value::Union(1, Box::new(b(Value::Union(1, Box::new(a))))),
+        ),
+    ]);
+    let data = encode(&value, &writer)?;
+    assert!(resolution.decode(&mut &data[..]).is_err());
+    Ok(())
+}
+
+#[test]
+fn incompatible() {
+    let writer = parse(
+        r#"{"type": "record", "name": "User", "fields": [
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "zip", "type": "int"}
+            ]}},
+            {"name": "id", "type": {"type": "fixed", "name": "Id", "size": 16}},
+            {"name": "tags", "type": {"type": "array", "items": "long"}}
+        ]}"#,
+    );
+    let reader = parse(
+        r#"{"type": "record", "name": "User", "fields": [
+            {"name": "address", "type": {"type": "record", "name": "Address", "fields": [
+                {"name": "zip", "type": "string"}
+            ]}},
+            {"name": "id", "type": {"type": "fixed", "name": "Id", "size": 8}},
+            {"name": "tags", "type": {"type": "array", "items": "int"}},
+            {"name": "age", "type": "int"}
+        ]}"#,
+    );
+    let error = resolve(&writer, &reader).unwrap_err();
+    let expected = vec![
+        ("User.address.zip", "type changed from int to string"),
+        ("User.id", "size changed from 16 to 8"),
+        ("User.tags.items", "type changed from long to int"),
+        (
+            "User.age",
+            "the field is missing from the writer's record and has no default",
+        ),
+    ]
+    .into_iter()
+    .map(|(path, reason)| Incompatibility {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    })
+    .collect::<Vec<_>>();
+    assert!(matches!(error, Error::IncompatibleSchemas(x) if x == expected));
+}
@@ -0,0 +1,110 @@
+use std::io::Cursor;
+
+use avro_schema::error::Error;
+use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
+use avro_schema::read::{Decoder, SeekableBlockStreamingIterator};
+use avro_schema::schema::Schema;
+use avro_schema::value::Value;
+
+use super::file::write_longs;
+
+fn reader(file: &[u8]) -> Result<SeekableBlockStreamingIterator<Cursor<&[u8]>>, Error> {
+    let mut reader = Cursor::new(file);
+    let metadata = avro_schema::read::read_metadata(&mut reader)?;
+    SeekableBlockStreamingIterator::try_new(reader, metadata.compression, metadata.marker)
+}
+
+/// Returns the values of the next block, if any
+fn next_values(
+    blocks: &mut SeekableBlockStreamingIterator<Cursor<&[u8]>>,
+) -> Result<Option<Vec<Value>>, Error> {
+    let schema = Schema::Long(None);
+    let decoder = Decoder::new(&schema);
+    let mut values = vec![];
+    match blocks.next()? {
+        Some(block) => decoder.decode_block(block, &mut values)?,
+        None => return Ok(None),
+    }
+    Ok(Some(values))
+}
+
+fn longs(values: &[i64]) -> Option<Vec<Value>> {
+    Some(values.iter().copied().map(Value::Long).collect())
+}
+
+#[test]
+fn index() -> Result<(), Error> {
+    let file = write_longs(7, 2, None)?;
+    let mut blocks = reader(&file)?;
+
+    // indexing does not move the iterator
+    assert_eq!(next_values(&mut blocks)?, longs(&[0, 1]));
+    let index = blocks.index()?.to_vec();
+    assert_eq!(
+        index
+            .iter()
+            .map(|x| (x.first_row, x.number_of_rows))
+            .collect::<Vec<_>>(),
+        vec![(0, 2), (2, 2), (4, 2), (6, 1)]
+    );
+    assert!(index.windows(2).all(|x| x[0].offset < x[1].offset));
+    assert_eq!(next_values(&mut blocks)?, longs(&[2, 3]));
+    Ok(())
+}
+
+#[test]
+fn seek_to_block() -> Result<(), Error> {
+    let file = write_longs(7, 2, None)?;
+    let mut blocks = reader(&file)?;
+
+    blocks.seek_to_block(2)?;
+    assert_eq!(next_values(&mut blocks)?, longs(&[4, 5]));
+    assert_eq!(next_values(&mut blocks)?, longs(&[6]));
+    assert_eq!(next_values(&mut blocks)?, None);
+
+    // backwards
+    blocks.seek_to_block(0)?;
+    assert_eq!(next_values(&mut blocks)?, longs(&[0, 1]));
+
+    // past the end
+    blocks.seek_to_block(10)?;
+    assert_eq!(next_values(&mut blocks)?, None);
+    Ok(())
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn seek_to_row() -> Result<(), Error> {
+    use avro_schema::file::Compression;
+
+    let file = write_longs(7, 2, Some(Compression::Deflate))?;
+    let mut blocks = reader(&file)?;
+
+    assert_eq!(blocks.seek_to_row(5)?, 1);
+    assert_eq!(next_values(&mut blocks)?, longs(&[4, 5]));
+
+    assert_eq!(blocks.seek_to_row(2)?, 0);
+    assert_eq!(next_values(&mut blocks)?, longs(&[2, 3]));
+
+    assert_eq!(blocks.seek_to_row(6)?, 0);
+    assert_eq!(next_values(&mut blocks)?, longs(&[6]));
+
+    assert_eq!(blocks.seek_to_row(7)?, 0);
+    assert_eq!(next_values(&mut blocks)?, None);
+    Ok(())
+}
+
+#[test]
+fn error_sync_marker_mismatch() -> Result<(), Error> {
+    let mut file = write_longs(4, 2, None)?;
+    let len = file.len();
+    file[len - 1] ^= 1;
+    let mut blocks = reader(&file)?;
+
+    blocks.seek_to_block(0)?;
+    match blocks.seek_to_block(1).unwrap_err() {
+        Error::SyncMarkerMismatch { block, .. } => assert_eq!(block, 1),
+        other => panic!("expected a sync marker mismatch, got {:?}", other),
+    }
+    Ok(())
+}
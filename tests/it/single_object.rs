@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use avro_schema::error::Error;
+use avro_schema::schema::Schema;
+use avro_schema::single_object::{decode, encode, read_header, MARKER};
+use avro_schema::value::Value;
+
+use super::decode::{expected_row, schema};
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let schema = schema();
+    let mut data = vec![];
+    encode(&expected_row(0), &schema, &mut data)?;
+
+    assert_eq!(data[..2], MARKER);
+    assert_eq!(data[2..10], schema.fingerprint_rabin().to_le_bytes());
+
+    let mut store = HashMap::new();
+    store.insert(schema.fingerprint_rabin(), schema.clone());
+    store.insert(Schema::Null(None).fingerprint_rabin(), Schema::Null(None));
+
+    let (value, read_schema) = decode(&mut &data[..], &store)?;
+    assert_eq!(value, expected_row(0));
+    assert_eq!(read_schema, &schema);
+    Ok(())
+}
+
+#[test]
+fn header() -> Result<(), Error> {
+    let mut data = vec![];
+    encode(&Value::Long(1), &Schema::Long(None), &mut data)?;
+    // fingerprint of "long" from the reference implementation's test suite
+    let fingerprint = -3434872931120570953i64 as u64;
+    assert_eq!(read_header(&mut &data[..])?, fingerprint);
+    assert_eq!(data[10..], [2]);
+    Ok(())
+}
+
+#[test]
+fn unknown_fingerprint() -> Result<(), Error> {
+    let mut data = vec![];
+    encode(&Value::Long(1), &Schema::Long(None), &mut data)?;
+
+    let mut store = HashMap::new();
+    store.insert(Schema::Null(None).fingerprint_rabin(), Schema::Null(None));
+
+    let error = decode(&mut &data[..], &store).unwrap_err();
+    assert!(
+        matches!(error, Error::UnknownFingerprint(x) if x == Schema::Long(None).fingerprint_rabin())
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_marker() {
+    let data = [b'O', b'b', 0, 0, 0, 0, 0, 0, 0, 0];
+    let error = decode(&mut &data[..], &HashMap::new()).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidSingleObjectMarker([b'O', b'b'])
+    ));
+
+    let error = read_header(&mut &MARKER[..]).unwrap_err();
+    assert!(matches!(error, Error::Io(_)));
+}
@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use avro_schema::error::Error;
+use avro_schema::read::fallible_streaming_iterator::FallibleStreamingIterator;
+use avro_schema::read::{Decoder, SplitBlockStreamingIterator};
+use avro_schema::value::Value;
+
+use super::file::write_longs;
+
+/// Returns the values of the blocks of `file` starting within `(start, len)`
+fn read_split(file: &[u8], start: u64, len: u64) -> Result<Vec<i64>, Error> {
+    let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+    let mut blocks =
+        SplitBlockStreamingIterator::try_new(Cursor::new(file), &metadata, start, len)?;
+
+    let decoder = Decoder::new(&metadata.schema);
+    let mut values = vec![];
+    while let Some(block) = blocks.next()? {
+        decoder.decode_block(block, &mut values)?;
+    }
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            Value::Long(value) => value,
+            _ => unreachable!(),
+        })
+        .collect())
+}
+
+#[test]
+fn splits_partition_the_file() -> Result<(), Error> {
+    let file = write_longs(20, 3, None)?;
+    let len = file.len() as u64;
+
+    for split in [1, 7, 16, 30, 50, len] {
+        let mut values = vec![];
+        let mut start = 0;
+        while start < len {
+            values.extend(read_split(&file, start, split)?);
+            start += split;
+        }
+        assert_eq!(
+            values,
+            (0..20).collect::<Vec<_>>(),
+            "splits of {} bytes",
+            split
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn split_within_a_block() -> Result<(), Error> {
+    let file = write_longs(20, 3, None)?;
+    let metadata = avro_schema::read::read_metadata(&mut &file[..])?;
+    // the first block starts right after the header's marker
+    let first = file.windows(16).position(|x| x == metadata.marker).unwrap() as u64 + 16;
+
+    // a split of a single byte contains the block starting at it
+    assert_eq!(read_split(&file, first, 1)?, vec![0, 1, 2]);
+    // no block starts within the first block
+    assert_eq!(read_split(&file, first + 1, 5)?, Vec::<i64>::new());
+    // past the end
+    assert_eq!(read_split(&file, file.len() as u64, 10)?, Vec::<i64>::new());
+    Ok(())
+}
+
+#[test]
+fn empty_block() -> Result<(), Error> {
+    let mut file = write_longs(6, 2, None)?;
+    let marker = avro_schema::read::read_metadata(&mut &file[..])?.marker;
+    // the start of the second block, after the header's and the first block's markers
+    let second = file
+        .windows(16)
+        .enumerate()
+        .filter(|(_, x)| *x == marker)
+        .nth(1)
+        .unwrap()
+        .0
+        + 16;
+    // a valid block without rows nor data before the second block
+    let empty = [&[0u8, 0][..], &marker[..]].concat();
+    file.splice(second..second, empty);
+
+    // the split ending within the block without rows does not read the block after it
+    assert_eq!(read_split(&file, 0, second as u64 + 1)?, vec![0, 1]);
+    let len = file.len() as u64;
+    assert_eq!(read_split(&file, second as u64 + 1, len)?, vec![2, 3, 4, 5]);
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use avro_schema::error::Error;
+use avro_schema::schema::{Field, Record, Schema, Violation};
+
+fn violations(json: &str) -> Vec<(String, String)> {
+    match Schema::from_json(json, true) {
+        Ok(_) => vec![],
+        Err(Error::SchemaViolations(violations)) => violations
+            .into_iter()
+            .map(|Violation { path, reason }| (path, reason))
+            .collect(),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+fn expected(violations: &[(&str, &str)]) -> Vec<(String, String)> {
+    violations
+        .iter()
+        .map(|(path, reason)| (path.to_string(), reason.to_string()))
+        .collect()
+}
+
+#[test]
+fn valid() -> Result<(), Error> {
+    let json = r#"{"type": "record", "name": "a.b.User", "fields": [
+        {"name": "_id", "type": ["null", "int", "long", {"type": "array", "items": "int"}]},
+        {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"], "default": "A"}},
+        {"name": "value", "type": {"type": "bytes", "logicalType": "decimal", "precision": 4, "scale": 4}},
+        {"name": "other", "type": ["null", "Kind", {"type": "enum", "name": "c.Kind", "symbols": ["A"]}]}
+    ]}"#;
+    Schema::from_json(json, true)?.validate()
+}
+
+#[test]
+fn invalid_names() {
+    let json = r#"{"type": "record", "name": "1User", "namespace": "a.-b", "fields": [
+        {"name": "a-b", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B C"]}}
+    ]}"#;
+    assert_eq!(
+        violations(json),
+        expected(&[
+            ("1User", "\"1User\" is not a valid name"),
+            ("1User", "\"a.-b\" is not a valid namespace"),
+            ("1User.a-b", "\"a-b\" is not a valid field name"),
+            ("1User.a-b", "\"B C\" is not a valid symbol"),
+        ])
+    );
+    // deserialization does not validate unless asked to
+    assert!(Schema::from_json(json, false).is_ok());
+}
+
+#[test]
+fn duplicates() {
+    let json = r#"{"type": "record", "name": "User", "fields": [
+        {"name": "a", "type": "int"},
+        {"name": "a", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "A"], "default": "B"}}
+    ]}"#;
+    assert_eq!(
+        violations(json),
+        expected(&[
+            ("User.a", "the field \"a\" is defined more than once"),
+            ("User.a", "the symbol \"A\" is defined more than once"),
+            ("User.a", "the default \"B\" is not a symbol"),
+        ])
+    );
+}
+
+#[test]
+fn unions() {
+    let json = r#"{"type": "map", "values": [
+        "null",
+        {"type": "array", "items": "int"},
+        {"type": "array", "items": "long"},
+        "int",
+        {"type": "int", "logicalType": "date"},
+        {"type": "fixed", "name": "A", "size": 1},
+        "A"
+    ]}"#;
+    assert_eq!(
+        violations(json),
+        expected(&[
+            ("map.values[2]", "the union contains array more than once"),
+            ("map.values[4]", "the union contains int more than once"),
+            ("map.values[6]", "the union contains \"A\" more than once"),
+        ])
+    );
+
+    let schema = Schema::Union(vec![
+        Schema::Null(None),
+        Schema::Union(vec![Schema::Int(None)]),
+    ]);
+    let error = schema.validate().unwrap_err();
+    assert!(
+        matches!(error, Error::SchemaViolations(x) if x == vec![Violation {
+            path: "union[1]".to_string(),
+            reason: "unions must not contain unions".to_string(),
+        }])
+    );
+}
+
+#[test]
+fn decimal() {
+    let json = r#"{"type": "record", "name": "User", "fields": [
+        {"name": "a", "type": {"type": "bytes", "logicalType": "decimal", "precision": 2, "scale": 3}},
+        {"name": "b", "type": {"type": "fixed", "name": "B", "size": 8, "logicalType": "decimal", "precision": 2, "scale": 3}}
+    ]}"#;
+    let reason = "the scale of the decimal (3) is larger than its precision (2)";
+    assert_eq!(
+        violations(json),
+        expected(&[("User.a", reason), ("User.b", reason)])
+    );
+}
+
+#[test]
+fn programmatic() {
+    let schema = Schema::Record(Record::new(
+        "User",
+        vec![
+            Field::new("a", Schema::Null(None)),
+            Field::new("a", Schema::Null(None)),
+        ],
+    ));
+    assert!(schema.validate().is_err());
+}